@@ -1,3 +1,40 @@
+use std::process::Command;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// 执行命令并返回去除首尾空白的输出，失败时返回 "unknown"
+fn command_output(program: &str, args: &[&str]) -> String {
+    Command::new(program)
+        .args(args)
+        .output()
+        .ok()
+        .filter(|output| output.status.success())
+        .map(|output| String::from_utf8_lossy(&output.stdout).trim().to_string())
+        .filter(|output| !output.is_empty())
+        .unwrap_or_else(|| "unknown".to_string())
+}
+
 fn main() {
+    // 嵌入构建信息，供 --version 和 get_build_info 使用
+    let git_hash = command_output("git", &["rev-parse", "--short", "HEAD"]);
+    let rustc = std::env::var("RUSTC").unwrap_or_else(|_| "rustc".to_string());
+    let rustc_version = command_output(&rustc, &["--version"]);
+    let target = std::env::var("TARGET").unwrap_or_else(|_| "unknown".to_string());
+    let build_timestamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_secs())
+        .unwrap_or_default();
+
+    println!("cargo:rustc-env=CUNZHI_GIT_HASH={}", git_hash);
+    println!("cargo:rustc-env=CUNZHI_RUSTC_VERSION={}", rustc_version);
+    println!("cargo:rustc-env=CUNZHI_BUILD_TARGET={}", target);
+    println!("cargo:rustc-env=CUNZHI_BUILD_TIMESTAMP={}", build_timestamp);
+    // 分支提交后更新 refs/heads，gc 后分支引用打包进 packed-refs，分离 HEAD 时直接写入 HEAD
+    println!("cargo:rerun-if-changed=.git/HEAD");
+    println!("cargo:rerun-if-changed=.git/refs/heads");
+    // 监听不存在的文件会导致每次都重新运行构建脚本
+    if std::path::Path::new(".git/packed-refs").exists() {
+        println!("cargo:rerun-if-changed=.git/packed-refs");
+    }
+
     tauri_build::build()
 }
@@ -67,6 +67,14 @@ export function useMcpHandler() {
       console.log('🔕 跳过前端弹窗显示，仅使用Telegram交互')
     }
 
+    // 发送系统通知（窗口已聚焦时后端会自动跳过）
+    try {
+      await invoke('send_request_notification', { request })
+    }
+    catch (error) {
+      console.error('发送系统通知失败:', error)
+    }
+
     // 播放音频通知（无论是否显示弹窗都播放）
     try {
       await invoke('play_notification_sound')
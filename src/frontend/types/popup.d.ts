@@ -5,6 +5,31 @@ export interface McpRequest {
   message: string
   predefined_options?: string[]
   is_markdown?: boolean
+  urgent?: boolean
+  expires_at?: number
+  trace_id?: string
+  remember_key?: string
+  input_constraints?: InputConstraints
+  allow_multiple?: boolean
+  confirm_options?: string[]
+  project_path?: string
+  attachments?: PopupAttachment[]
+}
+
+// 弹窗附件
+export interface PopupAttachment {
+  kind: 'image' | 'file'
+  mime: string
+  data_base64: string
+  name?: string
+}
+
+// 自由文本输入约束
+export interface InputConstraints {
+  pattern?: string
+  min_len?: number
+  max_len?: number
+  placeholder?: string
 }
 
 // 自定义prompt类型定义
@@ -11,6 +11,15 @@ pub fn build_tauri_app() -> Builder<tauri::Wry> {
     tauri::Builder::default()
         .plugin(tauri_plugin_shell::init())
         .plugin(tauri_plugin_updater::Builder::new().build())
+        .plugin(tauri_plugin_notification::init())
+        .plugin(tauri_plugin_dialog::init())
+        .plugin(
+            tauri_plugin_global_shortcut::Builder::new()
+                .with_handler(|app, shortcut, event| {
+                    crate::ui::handle_global_shortcut(app, shortcut, event)
+                })
+                .build(),
+        )
 
         .manage(AppState::default())
         .manage(AudioController {
@@ -19,6 +28,7 @@ pub fn build_tauri_app() -> Builder<tauri::Wry> {
         .invoke_handler(tauri::generate_handler![
             // 基础应用命令
             get_app_info,
+            get_build_info,
             get_always_on_top,
             set_always_on_top,
             sync_window_state,
@@ -35,6 +45,16 @@ pub fn build_tauri_app() -> Builder<tauri::Wry> {
             get_available_audio_assets,
             refresh_audio_assets,
 
+            // 系统通知命令
+            get_notify_on_request,
+            set_notify_on_request,
+            send_request_notification,
+            focus_popup_window,
+
+            // 全局快捷键命令
+            get_global_shortcut,
+            set_global_shortcut,
+
             // 主题和窗口命令
             get_theme,
             set_theme,
@@ -49,6 +69,9 @@ pub fn build_tauri_app() -> Builder<tauri::Wry> {
             get_current_window_size,
             apply_window_constraints,
             update_window_size,
+            get_focus_policy,
+            set_focus_policy,
+            reset_window_position,
 
             // 字体命令
             get_font_config,
@@ -65,12 +88,32 @@ pub fn build_tauri_app() -> Builder<tauri::Wry> {
             get_mcp_tools_status,
             reset_mcp_tools_config,
             send_mcp_response,
+            get_pending_count,
             get_cli_args,
             read_mcp_request,
             select_image_files,
             build_mcp_send_response,
             build_mcp_continue_response,
             create_test_popup,
+            test_webhook,
+            get_quiet_hours_config,
+            set_quiet_hours_config,
+            get_quiet_hours_state,
+            set_quiet_hours_override,
+            get_dnd_config,
+            set_dnd,
+            get_latency_stats,
+            attach_file_to_response,
+            save_response_draft,
+            get_response_draft,
+            list_popup_templates,
+            upsert_popup_template,
+            delete_popup_template,
+            validate_popup_input,
+            get_remembered_answers,
+            delete_remembered_answer,
+            clear_remembered_answers,
+            run_self_check,
             
             // acemcp命令（迁移至 tools::acemcp::commands）
             crate::mcp::tools::acemcp::commands::get_acemcp_config,
@@ -97,6 +140,7 @@ pub fn build_tauri_app() -> Builder<tauri::Wry> {
 
             // 配置管理命令
             get_config_file_path,
+            get_config_path,
 
             // Telegram 命令
             get_telegram_config,
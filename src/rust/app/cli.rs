@@ -1,85 +1,539 @@
-use crate::config::load_standalone_telegram_config;
+use clap::{CommandFactory, FromArgMatches, Parser, Subcommand, ValueEnum};
+use clap_complete::Shell;
+use std::io::Read;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, AtomicI32, AtomicI64, Ordering};
+use std::sync::OnceLock;
+
+use crate::config::{
+    config_path_override, get_standalone_config_path, load_standalone_config,
+    set_config_path_override, validate_config_file,
+};
+use crate::constants::{app, mcp};
+use crate::i18n::{t, tr, Msg};
+use crate::mcp::handlers::{classify_popup_response, create_tauri_popup, PopupOutcome};
+use crate::mcp::types::PopupRequest;
+use crate::mcp::utils::{generate_request_id, generate_trace_id};
 use crate::telegram::handle_telegram_only_mcp_request;
-use crate::log_important;
+use crate::utils::get_runtime_dir;
+use crate::{log_important, log_with_trace};
 use crate::app::builder::run_tauri_app;
+use crate::app::router::RequestRouter;
+use crate::app::self_check::{self_check, CheckStatus};
 use anyhow::Result;
 
-/// 处理命令行参数
-pub fn handle_cli_args() -> Result<()> {
-    let args: Vec<String> = std::env::args().collect();
+/// 当前进程处理的MCP请求文件（`--mcp-request` 模式下设置）
+static MCP_REQUEST_FILE: OnceLock<String> = OnceLock::new();
+
+/// 从标准输入读取请求时写入的临时文件，请求结束时删除
+static MCP_STDIN_REQUEST_FILE: OnceLock<PathBuf> = OnceLock::new();
+
+/// 当前进程的响应输出格式（`--mcp-request` 模式下设置）
+static MCP_RESPONSE_FORMAT: OnceLock<ResponseFormat> = OnceLock::new();
+
+/// 当前进程的退出码，未输出响应时视为用户取消
+static MCP_EXIT_CODE: AtomicI32 = AtomicI32::new(mcp::MCP_REQUEST_EXIT_CANCELLED);
+
+/// 是否已输出响应
+static MCP_RESPONSE_EMITTED: AtomicBool = AtomicBool::new(false);
+
+/// 请求展示给用户的时间（Unix毫秒时间戳，0 表示尚未展示）
+static UI_LAUNCHED_AT: AtomicI64 = AtomicI64::new(0);
+
+/// 表示从标准输入读取请求的文件参数
+const STDIN_REQUEST_FILE: &str = "-";
+
+/// 寸止支持的子命令，其余子命令仅等一下可用
+const SERVER_SUBCOMMANDS: [&str; 3] = ["send-test-popup", "validate-config", "self-check"];
+
+/// `--mcp-request` 响应输出格式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum ResponseFormat {
+    /// 直接输出响应内容（旧格式）
+    Text,
+    /// 输出 {"status", "response", "error"} 结果信封
+    Json,
+}
+
+/// 寸止 - 智能代码审查工具
+#[derive(Debug, Parser)]
+#[command(name = "等一下", about = "寸止 - 智能代码审查工具", disable_version_flag = true)]
+pub struct Cli {
+    /// 使用指定的配置文件（也可通过 CUNZHI_CONFIG_PATH 环境变量设置）
+    #[arg(long, global = true, value_name = "路径")]
+    pub config: Option<PathBuf>,
+
+    /// 日志级别（error, warn, info, debug, trace）
+    #[arg(long, global = true, value_name = "级别")]
+    pub log_level: Option<String>,
+
+    /// 以 JSON 格式输出结果
+    #[arg(long, global = true)]
+    pub json: bool,
+
+    /// 显示版本信息
+    #[arg(short = 'v', long)]
+    pub version: bool,
+
+    #[command(subcommand)]
+    pub command: Option<CliCommand>,
+}
+
+/// 子命令（均可使用 `--子命令` 的旧写法调用）
+#[derive(Debug, Subcommand)]
+pub enum CliCommand {
+    /// 启动设置界面
+    Ui,
+
+    /// 处理 MCP 请求
+    #[command(long_flag = "mcp-request")]
+    McpRequest {
+        /// 请求文件路径，`-` 表示从标准输入读取
+        #[arg(value_name = "文件")]
+        file: String,
+
+        /// 响应输出格式（退出码: 0 已回答, 2 取消, 3 超时, 4 内部错误）
+        #[arg(long, value_enum, default_value = "text")]
+        response_format: ResponseFormat,
+    },
+
+    /// 发送测试弹窗并输出结果（退出码: 0 已回答, 2 超时, 3 取消, 4 失败）
+    #[command(long_flag = "send-test-popup")]
+    SendTestPopup {
+        /// 弹窗消息
+        message: String,
+
+        /// 预定义选项，以逗号分隔
+        #[arg(long, value_delimiter = ',')]
+        options: Vec<String>,
+
+        /// 等待超时（秒）
+        #[arg(long, value_name = "秒")]
+        timeout: Option<u64>,
+    },
+
+    /// 检查配置文件
+    #[command(long_flag = "validate-config")]
+    ValidateConfig {
+        /// 配置文件路径，默认为当前配置文件
+        #[arg(value_name = "路径")]
+        path: Option<PathBuf>,
+    },
+
+    /// 检查本地运行环境（退出码: 0 无失败项, 1 存在失败项）
+    #[command(long_flag = "self-check")]
+    SelfCheck {
+        /// 同时进行网络检查（如验证 Telegram Bot Token）
+        #[arg(long)]
+        network: bool,
+    },
+
+    /// 生成 shell 补全脚本
+    #[command(long_flag = "completions", hide = true)]
+    Completions {
+        #[arg(value_enum)]
+        shell: Shell,
+    },
+}
+
+/// 解析等一下的命令行参数，参数错误时以退出码 1 退出
+pub fn parse_cli() -> Cli {
+    parse_cli_with(Cli::command())
+}
+
+/// 解析寸止的命令行参数，帮助信息中只列出寸止支持的子命令
+pub fn parse_server_cli() -> Cli {
+    let command = Cli::command()
+        .name("寸止")
+        .bin_name("寸止")
+        .mut_subcommands(|subcommand| {
+            let supported = SERVER_SUBCOMMANDS.contains(&subcommand.get_name());
+            subcommand.hide(!supported)
+        });
+    parse_cli_with(command)
+}
 
-    match args.len() {
-        // 无参数：正常启动GUI
-        1 => {
-            run_tauri_app();
+fn parse_cli_with(command: clap::Command) -> Cli {
+    command
+        .try_get_matches()
+        .and_then(|matches| Cli::from_arg_matches(&matches))
+        .unwrap_or_else(|e| {
+            let _ = e.print();
+            std::process::exit(if e.use_stderr() { 1 } else { 0 });
+        })
+}
+
+/// 应用全局选项，需在初始化日志系统之前调用
+pub fn apply_global_options(cli: &Cli) -> Result<()> {
+    if let Some(config_path) = &cli.config {
+        set_config_path_override(config_path)?;
+    }
+
+    if let Some(log_level) = &cli.log_level {
+        std::env::set_var("RUST_LOG", log_level);
+    }
+
+    Ok(())
+}
+
+/// 记录 `--mcp-request` 的请求文件和输出格式，仅等一下调用，需在初始化日志系统之前调用
+///
+/// 请求文件为 `-` 时从标准输入读取
+pub fn prepare_mcp_request(cli: &Cli) {
+    if let Some(CliCommand::McpRequest { file, response_format }) = &cli.command {
+        let _ = MCP_RESPONSE_FORMAT.set(*response_format);
+        let request_file = if file == STDIN_REQUEST_FILE {
+            read_mcp_request_from_stdin()
+        } else {
+            file.clone()
+        };
+        let _ = MCP_REQUEST_FILE.set(request_file);
+    }
+}
+
+/// 从标准输入读取 MCP 请求并写入临时文件，返回文件路径
+///
+/// 后续的 GUI 和 Telegram 流程仍按文件模式处理；输入无效时直接退出
+fn read_mcp_request_from_stdin() -> String {
+    let mut content = String::new();
+    if let Err(e) = std::io::stdin().read_to_string(&mut content) {
+        eprintln!("读取标准输入失败: {}", e);
+        std::process::exit(mcp::MCP_REQUEST_EXIT_INVALID_INPUT);
+    }
+
+    if let Err(e) = serde_json::from_str::<PopupRequest>(&content) {
+        eprintln!("标准输入不是有效的MCP请求JSON: {}", e);
+        std::process::exit(mcp::MCP_REQUEST_EXIT_INVALID_INPUT);
+    }
+
+    let temp_file = match get_runtime_dir() {
+        Ok(dir) => dir.join(format!("{}stdin_{}.json", app::REQUEST_FILE_PREFIX, std::process::id())),
+        Err(e) => {
+            eprintln!("获取运行时目录失败: {}", e);
+            std::process::exit(mcp::MCP_REQUEST_EXIT_INVALID_INPUT);
         }
-        // 单参数：帮助或版本
-        2 => {
-            match args[1].as_str() {
-                "--help" | "-h" => print_help(),
-                "--version" | "-v" => print_version(),
-                _ => {
-                    eprintln!("未知参数: {}", args[1]);
-                    print_help();
-                    std::process::exit(1);
-                }
-            }
+    };
+    if let Err(e) = std::fs::write(&temp_file, content) {
+        eprintln!("写入临时请求文件失败: {}", e);
+        std::process::exit(mcp::MCP_REQUEST_EXIT_INVALID_INPUT);
+    }
+
+    let path = temp_file.to_string_lossy().to_string();
+    let _ = MCP_STDIN_REQUEST_FILE.set(temp_file);
+    path
+}
+
+/// 当前进程是否处于 MCP 请求模式
+pub fn is_mcp_request_mode() -> bool {
+    mcp_request_file().is_some()
+}
+
+/// 当前进程处理的 MCP 请求文件路径
+pub fn mcp_request_file() -> Option<String> {
+    MCP_REQUEST_FILE.get().cloned()
+}
+
+/// 记录请求已展示给用户的时间，用于统计各阶段耗时
+pub fn mark_ui_launched() {
+    let _ = UI_LAUNCHED_AT.compare_exchange(0, chrono::Utc::now().timestamp_millis(), Ordering::SeqCst, Ordering::SeqCst);
+}
+
+/// 输出 MCP 响应并记录对应的退出码
+pub fn emit_mcp_response(response: &str) -> std::io::Result<()> {
+    let outcome = classify_popup_response(response);
+    MCP_EXIT_CODE.store(outcome.exit_code(), Ordering::SeqCst);
+    MCP_RESPONSE_EMITTED.store(true, Ordering::SeqCst);
+
+    if MCP_RESPONSE_FORMAT.get() == Some(&ResponseFormat::Json) {
+        let response_value = match outcome {
+            PopupOutcome::Answered => serde_json::from_str(response)
+                .unwrap_or_else(|_| serde_json::Value::String(response.to_string())),
+            _ => serde_json::Value::Null,
+        };
+        println!(
+            "{}",
+            serde_json::json!({
+                "status": outcome.as_str(),
+                "response": response_value,
+                "error": null,
+                "ui_launched_at": Some(UI_LAUNCHED_AT.load(Ordering::SeqCst)).filter(|at| *at > 0),
+            })
+        );
+    } else {
+        println!("{}", response);
+    }
+
+    std::io::Write::flush(&mut std::io::stdout())
+}
+
+/// 输出 MCP 错误结果（仅 JSON 格式下输出信封）并记录退出码
+pub fn emit_mcp_error(error: &str) {
+    MCP_EXIT_CODE.store(mcp::MCP_REQUEST_EXIT_ERROR, Ordering::SeqCst);
+    MCP_RESPONSE_EMITTED.store(true, Ordering::SeqCst);
+
+    if MCP_RESPONSE_FORMAT.get() == Some(&ResponseFormat::Json) {
+        println!(
+            "{}",
+            serde_json::json!({ "status": "error", "response": null, "error": error })
+        );
+    }
+}
+
+/// 以取消结束尚未响应的 MCP 请求，返回是否执行了取消（已响应时不做处理）
+///
+/// 文本格式下保持空输出（调用方视为取消），JSON 格式下输出带原因的取消结果
+pub fn cancel_pending_mcp_request(reason: &str) -> bool {
+    if MCP_RESPONSE_EMITTED.swap(true, Ordering::SeqCst) {
+        return false;
+    }
+
+    MCP_EXIT_CODE.store(mcp::MCP_REQUEST_EXIT_CANCELLED, Ordering::SeqCst);
+    if MCP_RESPONSE_FORMAT.get() == Some(&ResponseFormat::Json) {
+        println!(
+            "{}",
+            serde_json::json!({ "status": "cancelled", "response": null, "error": reason })
+        );
+    }
+    true
+}
+
+/// 结束 MCP 请求，返回进程退出码
+///
+/// 未输出任何响应时视为用户取消，JSON 格式下补充输出取消结果；
+/// 从标准输入读取的请求会在此删除对应的临时文件
+pub fn finish_mcp_request() -> i32 {
+    if let Some(temp_file) = MCP_STDIN_REQUEST_FILE.get().filter(|file| file.exists()) {
+        if let Err(e) = std::fs::remove_file(temp_file) {
+            log_important!(warn, "删除临时请求文件失败: {}, 路径: {}", e, temp_file.display());
         }
-        // 多参数：MCP请求模式
-        _ => {
-            if args[1] == "--mcp-request" && args.len() >= 3 {
-                handle_mcp_request(&args[2])?;
-            } else {
-                eprintln!("无效的命令行参数");
-                print_help();
-                std::process::exit(1);
+    }
+
+    if !MCP_RESPONSE_EMITTED.load(Ordering::SeqCst) && MCP_RESPONSE_FORMAT.get() == Some(&ResponseFormat::Json) {
+        println!(
+            "{}",
+            serde_json::json!({ "status": "cancelled", "response": null, "error": null })
+        );
+    }
+
+    MCP_EXIT_CODE.load(Ordering::SeqCst)
+}
+
+/// 处理命令行参数
+pub fn handle_cli_args(cli: Cli) -> Result<()> {
+    if let Some(config_path) = config_path_override() {
+        log_important!(info, "使用配置文件: {}", config_path.display());
+    }
+
+    if cli.version {
+        print_version();
+        return Ok(());
+    }
+
+    match cli.command {
+        None | Some(CliCommand::Ui) => run_tauri_app(),
+        Some(CliCommand::McpRequest { .. }) => {
+            // 使用解析后的请求文件（`-` 已替换为标准输入内容的临时文件）
+            if let Some(request_file) = mcp_request_file() {
+                handle_mcp_request(&request_file)?;
             }
         }
+        Some(CliCommand::SendTestPopup { message, options, timeout }) => {
+            let exit_code = tokio::runtime::Runtime::new()?
+                .block_on(run_send_test_popup(message, options, timeout));
+            std::process::exit(exit_code);
+        }
+        Some(CliCommand::ValidateConfig { path }) => {
+            std::process::exit(run_validate_config(path, cli.json));
+        }
+        Some(CliCommand::SelfCheck { network }) => {
+            let exit_code = tokio::runtime::Runtime::new()?.block_on(run_self_check_cli(network, cli.json));
+            std::process::exit(exit_code);
+        }
+        Some(CliCommand::Completions { shell }) => {
+            let mut command = Cli::command();
+            let name = command.get_name().to_string();
+            clap_complete::generate(shell, &mut command, name, &mut std::io::stdout());
+        }
     }
 
     Ok(())
 }
 
-/// 处理MCP请求
-fn handle_mcp_request(request_file: &str) -> Result<()> {
-    // 检查Telegram配置，决定是否启用纯Telegram模式
-    match load_standalone_telegram_config() {
-        Ok(telegram_config) => {
-            if telegram_config.enabled && telegram_config.hide_frontend_popup {
-                // 纯Telegram模式：不启动GUI，直接处理
-                if let Err(e) = tokio::runtime::Runtime::new()
-                    .unwrap()
-                    .block_on(handle_telegram_only_mcp_request(request_file))
-                {
-                    log_important!(error, "处理Telegram请求失败: {}", e);
-                    std::process::exit(1);
-                }
-            } else {
-                // 正常模式：启动GUI处理弹窗
-                run_tauri_app();
+/// 检查配置文件并输出报告，返回进程退出码（0 通过，1 存在错误）
+///
+/// 未指定路径时使用当前配置文件（遵循 `--config`）
+pub fn run_validate_config(path: Option<PathBuf>, json_output: bool) -> i32 {
+    let config_path = match path {
+        Some(path) => path,
+        None => match get_standalone_config_path() {
+            Ok(path) => path,
+            Err(e) => {
+                eprintln!("获取配置文件路径失败: {}", e);
+                return 1;
             }
+        },
+    };
+
+    let findings = validate_config_file(&config_path);
+    let has_error = findings.iter().any(|finding| finding.is_error());
+
+    if json_output {
+        let report = serde_json::json!({
+            "config_path": config_path.to_string_lossy(),
+            "valid": !has_error,
+            "findings": findings,
+        });
+        println!("{}", serde_json::to_string_pretty(&report).unwrap_or_default());
+    } else {
+        println!("{}", tr(Msg::CliConfigFile, &[&config_path.display()]));
+        if findings.is_empty() {
+            println!("{}", t(Msg::CliNoFindings));
         }
-        Err(e) => {
-            log_important!(warn, "加载Telegram配置失败: {}，使用默认GUI模式", e);
-            // 配置加载失败时，使用默认行为（启动GUI）
-            run_tauri_app();
+        for finding in &findings {
+            let label = t(if finding.is_error() { Msg::CliError } else { Msg::CliWarn });
+            let location = if finding.path.is_empty() { "-" } else { finding.path.as_str() };
+            println!("[{}] {}: {}", label, location, finding.message);
         }
     }
-    Ok(())
+
+    if has_error { 1 } else { 0 }
+}
+
+/// 执行环境自检并输出结果，返回进程退出码
+pub async fn run_self_check_cli(network: bool, json_output: bool) -> i32 {
+    let report = self_check(network).await;
+
+    if json_output {
+        println!("{}", serde_json::to_string_pretty(&report).unwrap_or_default());
+    } else {
+        for check in &report.checks {
+            let label = t(match check.status {
+                CheckStatus::Pass => Msg::CliPass,
+                CheckStatus::Warn => Msg::CliWarn,
+                CheckStatus::Fail => Msg::CliFail,
+            });
+            println!("[{}] {}: {}", label, check.name, check.message);
+            if let Some(hint) = &check.hint {
+                println!("       {}", tr(Msg::CliHint, &[hint]));
+            }
+        }
+    }
+
+    if report.ok { 0 } else { 1 }
+}
+
+/// 测试弹窗退出码：用户已回答
+const TEST_POPUP_EXIT_ANSWERED: i32 = 0;
+/// 测试弹窗退出码：等待超时
+const TEST_POPUP_EXIT_TIMEOUT: i32 = 2;
+/// 测试弹窗退出码：用户取消
+const TEST_POPUP_EXIT_CANCELLED: i32 = 3;
+/// 测试弹窗退出码：弹窗调用失败
+const TEST_POPUP_EXIT_FAILED: i32 = 4;
+
+/// 发送测试弹窗并输出结构化结果，返回进程退出码
+///
+/// 与 MCP 服务器使用相同的配置和弹窗调用路径，用于端到端验证
+pub async fn run_send_test_popup(message: String, options: Vec<String>, timeout_secs: Option<u64>) -> i32 {
+    let request = PopupRequest {
+        id: generate_request_id(),
+        message,
+        predefined_options: if options.is_empty() { None } else { Some(options) },
+        is_markdown: true,
+        urgent: false,
+        expires_at: None,
+        trace_id: Some(generate_trace_id()),
+        remember_key: None,
+        input_constraints: None,
+        allow_multiple: true,
+        attachments: Vec::new(),
+        confirm_options: Vec::new(),
+        sent_at: Some(chrono::Utc::now().timestamp_millis()),
+        project_path: None,
+    };
+
+    let popup = tokio::task::spawn_blocking(move || create_tauri_popup(&request));
+    let result = match timeout_secs {
+        Some(secs) => match tokio::time::timeout(std::time::Duration::from_secs(secs), popup).await {
+            Ok(result) => result,
+            Err(_) => {
+                // 超时后弹窗进程仍会保留，由用户自行关闭
+                println!("{}", serde_json::json!({ "status": "timeout" }));
+                return TEST_POPUP_EXIT_TIMEOUT;
+            }
+        },
+        None => popup.await,
+    };
+
+    let response = match result.map_err(anyhow::Error::from).and_then(|response| response) {
+        Ok(response) => response,
+        Err(e) => {
+            println!("{}", serde_json::json!({ "status": "error", "error": e.to_string() }));
+            return TEST_POPUP_EXIT_FAILED;
+        }
+    };
+
+    let outcome = classify_popup_response(&response);
+    let exit_code = match outcome {
+        PopupOutcome::Answered => TEST_POPUP_EXIT_ANSWERED,
+        PopupOutcome::Cancelled => TEST_POPUP_EXIT_CANCELLED,
+        PopupOutcome::Expired => TEST_POPUP_EXIT_TIMEOUT,
+    };
+    let parsed = serde_json::from_str::<serde_json::Value>(&response)
+        .unwrap_or_else(|_| serde_json::Value::String(response.clone()));
+
+    println!("{}", serde_json::json!({ "status": outcome.as_str(), "response": parsed }));
+    exit_code
 }
 
-/// 显示帮助信息
-fn print_help() {
-    println!("寸止 - 智能代码审查工具");
-    println!();
-    println!("用法:");
-    println!("  等一下                    启动设置界面");
-    println!("  等一下 --mcp-request <文件>  处理 MCP 请求");
-    println!("  等一下 --help             显示此帮助信息");
-    println!("  等一下 --version          显示版本信息");
+/// 处理MCP请求
+///
+/// 由 `RequestRouter` 决定处理方式，仅在计划包含本地渠道时启动界面
+fn handle_mcp_request(request_file: &str) -> Result<()> {
+    let request: Option<PopupRequest> = std::fs::read_to_string(request_file)
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok());
+    let config = load_standalone_config()
+        .map_err(|e| log_important!(warn, "加载配置失败: {}", e))
+        .ok();
+
+    let plan = RequestRouter::new(config.as_ref(), request.as_ref()).plan();
+    for warning in &plan.warnings {
+        log_important!(warn, "{}", warning);
+    }
+
+    // 已过期的请求不显示界面，直接返回过期响应
+    if plan.expired {
+        if let Some(request) = &request {
+            log_with_trace!(info, request.trace_label(), "请求已过期，不显示弹窗: id={}", request.id);
+        }
+        emit_mcp_response(mcp::EXPIRED_RESPONSE)?;
+        std::process::exit(finish_mcp_request());
+    }
+
+    if plan.includes_local() {
+        // 正常模式：启动GUI处理弹窗
+        run_tauri_app();
+        return Ok(());
+    }
+
+    // 纯Telegram模式：不启动GUI，直接处理
+    let result = tokio::runtime::Runtime::new()
+        .map_err(anyhow::Error::from)
+        .and_then(|runtime| runtime.block_on(handle_telegram_only_mcp_request(request_file)));
+    if let Err(e) = result {
+        log_important!(error, "处理Telegram请求失败: {}", e);
+        emit_mcp_error(&e.to_string());
+    }
+    std::process::exit(finish_mcp_request());
 }
 
 /// 显示版本信息
-fn print_version() {
-    println!("寸止 v{}", env!("CARGO_PKG_VERSION"));
+pub fn print_version() {
+    let build_info = app::get_build_info();
+    println!("寸止 v{}", build_info.version);
+    println!("提交: {}", build_info.git_hash);
+    println!("构建时间: {}", build_info.build_time);
+    println!("目标平台: {}", build_info.target);
+    println!("编译器: {}", build_info.rustc_version);
 }
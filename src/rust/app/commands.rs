@@ -7,6 +7,9 @@ pub use crate::mcp::commands::*;
 pub use crate::telegram::commands::*;
 pub use crate::telegram::handle_telegram_only_mcp_request;
 
+// 自检命令
+pub use crate::app::self_check::run_self_check;
+
 // UI 命令
 pub use crate::ui::{
     commands::*,
@@ -17,4 +20,8 @@ pub use crate::ui::{
     updater::*,
     exit::*,
     exit_handler::*,
+    notification::*,
+    global_shortcut::*,
+    pending::*,
+    drafts::*,
 };
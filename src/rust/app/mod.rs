@@ -2,8 +2,12 @@ pub mod setup;
 pub mod commands;
 pub mod builder;
 pub mod cli;
+pub mod self_check;
+pub mod router;
 
 pub use setup::*;
 pub use commands::*;
 pub use builder::*;
 pub use cli::*;
+pub use self_check::*;
+pub use router::*;
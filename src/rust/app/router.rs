@@ -0,0 +1,166 @@
+use chrono::NaiveDateTime;
+
+use crate::config::AppConfig;
+use crate::constants::mcp;
+use crate::mcp::handlers::{quiet_hours_action, QuietHoursAction};
+use crate::mcp::types::PopupRequest;
+use crate::mcp::utils::is_expired_at;
+
+/// 请求处理渠道
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RequestChannel {
+    /// 本地等一下界面（界面内会同时启动 Telegram 同步）
+    Local,
+    /// 不启动界面，仅通过 Telegram 交互
+    Telegram,
+}
+
+/// `--mcp-request` 的处理计划
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RequestPlan {
+    pub expired: bool,                  // 请求已过期，直接返回过期响应
+    pub channels: Vec<RequestChannel>,  // 按优先级排列的处理渠道
+    pub warnings: Vec<String>,          // 配置冲突等需要记录的提示
+}
+
+impl RequestPlan {
+    /// 是否需要启动本地界面
+    pub fn includes_local(&self) -> bool {
+        self.channels.contains(&RequestChannel::Local)
+    }
+}
+
+/// 根据配置和请求决定 `--mcp-request` 的处理方式
+///
+/// 只做判断，不启动任何进程，便于测试
+pub struct RequestRouter<'a> {
+    config: Option<&'a AppConfig>,
+    request: Option<&'a PopupRequest>,
+    now_ms: i64,
+    now_local: NaiveDateTime,
+}
+
+impl<'a> RequestRouter<'a> {
+    /// 配置加载失败时传入 None，按默认界面模式处理
+    pub fn new(config: Option<&'a AppConfig>, request: Option<&'a PopupRequest>) -> Self {
+        Self {
+            config,
+            request,
+            now_ms: chrono::Utc::now().timestamp_millis(),
+            now_local: chrono::Local::now().naive_local(),
+        }
+    }
+
+    /// 指定判断使用的当前时间
+    pub fn at(mut self, now_ms: i64, now_local: NaiveDateTime) -> Self {
+        self.now_ms = now_ms;
+        self.now_local = now_local;
+        self
+    }
+
+    pub fn plan(&self) -> RequestPlan {
+        let mut warnings = Vec::new();
+
+        let skew_ms = self
+            .config
+            .and_then(|config| config.mcp_config.expiry_clock_skew_ms)
+            .unwrap_or(mcp::DEFAULT_EXPIRY_CLOCK_SKEW_MS);
+        if self
+            .request
+            .is_some_and(|request| is_expired_at(request.expires_at, self.now_ms, skew_ms))
+        {
+            return RequestPlan { expired: true, channels: Vec::new(), warnings };
+        }
+
+        let Some(config) = self.config else {
+            warnings.push("加载配置失败，使用默认GUI模式".to_string());
+            return RequestPlan { expired: false, channels: vec![RequestChannel::Local], warnings };
+        };
+
+        // 勿扰模式已由寸止在启动等一下之前处理，这里不再改变渠道
+        let telegram_config = &config.telegram_config;
+        let quiet_telegram_only =
+            quiet_hours_action(&config.quiet_hours, self.now_local) == QuietHoursAction::TelegramOnly;
+        if quiet_telegram_only && !telegram_config.enabled {
+            warnings.push("免打扰时段要求仅使用Telegram，但Telegram未启用，使用GUI模式".to_string());
+        }
+
+        let channels = if !telegram_config.enabled {
+            vec![RequestChannel::Local]
+        } else if telegram_config.hide_frontend_popup || quiet_telegram_only {
+            vec![RequestChannel::Telegram]
+        } else {
+            vec![RequestChannel::Local, RequestChannel::Telegram]
+        };
+
+        RequestPlan { expired: false, channels, warnings }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(value: &str) -> NaiveDateTime {
+        NaiveDateTime::parse_from_str(value, "%Y-%m-%d %H:%M").unwrap()
+    }
+
+    fn plan(config: &AppConfig, now: &str) -> RequestPlan {
+        RequestRouter::new(Some(config), None).at(0, at(now)).plan()
+    }
+
+    #[test]
+    fn test_route_by_config() {
+        let mut config = AppConfig::default();
+        assert_eq!(plan(&config, "2025-01-07 12:00").channels, vec![RequestChannel::Local]);
+
+        config.telegram_config.enabled = true;
+        assert_eq!(
+            plan(&config, "2025-01-07 12:00").channels,
+            vec![RequestChannel::Local, RequestChannel::Telegram]
+        );
+
+        config.telegram_config.hide_frontend_popup = true;
+        assert_eq!(plan(&config, "2025-01-07 12:00").channels, vec![RequestChannel::Telegram]);
+
+        // 勿扰模式不影响等一下的渠道选择
+        config.dnd.enabled = true;
+        assert_eq!(plan(&config, "2025-01-07 12:00").channels, vec![RequestChannel::Telegram]);
+    }
+
+    #[test]
+    fn test_route_quiet_hours_telegram_only() {
+        let mut config = AppConfig::default();
+        config.quiet_hours.enabled = true;
+        config.quiet_hours.behavior = mcp::QUIET_HOURS_TELEGRAM_ONLY.to_string();
+
+        let quiet = plan(&config, "2025-01-06 23:30");
+        assert_eq!(quiet.channels, vec![RequestChannel::Local]);
+        assert_eq!(quiet.warnings.len(), 1);
+
+        config.telegram_config.enabled = true;
+        assert_eq!(plan(&config, "2025-01-06 23:30").channels, vec![RequestChannel::Telegram]);
+        assert!(plan(&config, "2025-01-07 12:00").includes_local());
+    }
+
+    #[test]
+    fn test_route_without_config_or_expired() {
+        let fallback = RequestRouter::new(None, None).plan();
+        assert_eq!(fallback.channels, vec![RequestChannel::Local]);
+        assert!(!fallback.warnings.is_empty());
+
+        let request: PopupRequest = serde_json::from_value(serde_json::json!({
+            "id": "r1",
+            "message": "hi",
+            "is_markdown": false,
+            "expires_at": 1_000,
+        }))
+        .unwrap();
+        let config = AppConfig::default();
+        let expired = RequestRouter::new(Some(&config), Some(&request))
+            .at(1_000 + mcp::DEFAULT_EXPIRY_CLOCK_SKEW_MS as i64 + 1, at("2025-01-07 12:00"))
+            .plan();
+        assert!(expired.expired);
+        assert!(expired.channels.is_empty());
+    }
+}
@@ -0,0 +1,172 @@
+use serde::Serialize;
+
+use crate::config::{get_standalone_config_path, load_standalone_config, validate_config_file};
+use crate::constants::telegram as telegram_constants;
+use crate::mcp::handlers::{find_ui_command, test_command_available};
+use crate::ui::embedded_audio_asset_count;
+
+/// 自检项结果状态
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CheckStatus {
+    Pass,
+    Warn,
+    Fail,
+}
+
+/// 单个自检项的结果
+#[derive(Debug, Clone, Serialize)]
+pub struct SelfCheckItem {
+    pub name: String, // 检查项标识，如 "config"
+    pub status: CheckStatus,
+    pub message: String,
+    pub hint: Option<String>, // 未通过时的处理建议
+}
+
+impl SelfCheckItem {
+    fn pass(name: &str, message: String) -> Self {
+        Self { name: name.to_string(), status: CheckStatus::Pass, message, hint: None }
+    }
+
+    fn warn(name: &str, message: String, hint: &str) -> Self {
+        Self { name: name.to_string(), status: CheckStatus::Warn, message, hint: Some(hint.to_string()) }
+    }
+
+    fn fail(name: &str, message: String, hint: &str) -> Self {
+        Self { name: name.to_string(), status: CheckStatus::Fail, message, hint: Some(hint.to_string()) }
+    }
+}
+
+/// 自检报告
+#[derive(Debug, Clone, Serialize)]
+pub struct SelfCheckReport {
+    pub ok: bool, // 没有失败项
+    pub checks: Vec<SelfCheckItem>,
+}
+
+/// 执行本地环境自检
+///
+/// `network` 为 true 时才会请求 Telegram API 验证 Token
+pub async fn self_check(network: bool) -> SelfCheckReport {
+    let checks = vec![
+        check_config(),
+        check_ui_command(),
+        check_audio_assets(),
+        check_telegram(network).await,
+    ];
+    let ok = checks.iter().all(|check| check.status != CheckStatus::Fail);
+    SelfCheckReport { ok, checks }
+}
+
+/// 配置文件可读且有效
+fn check_config() -> SelfCheckItem {
+    const NAME: &str = "config";
+
+    let config_path = match get_standalone_config_path() {
+        Ok(path) => path,
+        Err(e) => return SelfCheckItem::fail(NAME, format!("获取配置文件路径失败: {}", e), "检查用户配置目录是否存在且可写"),
+    };
+
+    if !config_path.exists() {
+        return SelfCheckItem::warn(
+            NAME,
+            format!("配置文件不存在，将使用默认配置: {}", config_path.display()),
+            "打开设置界面保存一次即可生成配置文件",
+        );
+    }
+
+    let findings = validate_config_file(&config_path);
+    if let Some(error) = findings.iter().find(|finding| finding.is_error()) {
+        return SelfCheckItem::fail(
+            NAME,
+            format!("{}: {}", config_path.display(), error.message),
+            "运行 `等一下 --validate-config` 查看全部问题，或删除配置文件后重新生成",
+        );
+    }
+    if !findings.is_empty() {
+        return SelfCheckItem::warn(
+            NAME,
+            format!("{}: 发现 {} 个警告", config_path.display(), findings.len()),
+            "运行 `等一下 --validate-config` 查看详情",
+        );
+    }
+
+    SelfCheckItem::pass(NAME, format!("{}", config_path.display()))
+}
+
+/// 等一下命令可被找到并响应 `--version`
+fn check_ui_command() -> SelfCheckItem {
+    const NAME: &str = "ui_command";
+    const HINT: &str = "运行 cargo build --release 或 ./install.sh，确保等一下与寸止位于同一目录或在 PATH 中";
+
+    let command = match find_ui_command() {
+        Ok(command) => command,
+        Err(_) => return SelfCheckItem::fail(NAME, "找不到等一下命令".to_string(), HINT),
+    };
+
+    if !test_command_available(&command) {
+        return SelfCheckItem::fail(NAME, format!("等一下无法响应 --version: {}", command), HINT);
+    }
+
+    SelfCheckItem::pass(NAME, command)
+}
+
+/// 内嵌音效资源存在
+fn check_audio_assets() -> SelfCheckItem {
+    const NAME: &str = "audio_assets";
+
+    match embedded_audio_asset_count() {
+        0 => SelfCheckItem::warn(
+            NAME,
+            "没有内嵌音效资源，提示音将不可用".to_string(),
+            "确认 src/rust/assets/resources/ 中包含音频文件后重新编译",
+        ),
+        count => SelfCheckItem::pass(NAME, format!("{} 个内嵌音效", count)),
+    }
+}
+
+/// Telegram 配置完整，`network` 为 true 时验证 Token
+async fn check_telegram(network: bool) -> SelfCheckItem {
+    const NAME: &str = "telegram";
+
+    let config = match load_standalone_config() {
+        Ok(config) => config.telegram_config,
+        Err(e) => return SelfCheckItem::fail(NAME, format!("读取配置失败: {}", e), "先修复配置文件"),
+    };
+
+    if !config.enabled {
+        return SelfCheckItem::pass(NAME, "未启用".to_string());
+    }
+
+    if config.bot_token.trim().is_empty() || config.chat_id.trim().is_empty() {
+        return SelfCheckItem::fail(
+            NAME,
+            "已启用但缺少 Bot Token 或 Chat ID".to_string(),
+            "在设置界面的 Telegram 页面填写 Bot Token 和 Chat ID",
+        );
+    }
+
+    if !network {
+        return SelfCheckItem::pass(NAME, "配置完整（未进行网络检查）".to_string());
+    }
+
+    let api_url = if config.api_base_url == telegram_constants::API_BASE_URL {
+        None
+    } else {
+        Some(config.api_base_url.as_str())
+    };
+    match crate::telegram::core::test_telegram_connection_with_api_url(&config.bot_token, &config.chat_id, api_url).await {
+        Ok(_) => SelfCheckItem::pass(NAME, "Bot Token 有效".to_string()),
+        Err(e) => SelfCheckItem::fail(
+            NAME,
+            format!("连接测试失败: {}", e),
+            "检查 Bot Token、Chat ID、API 地址以及网络代理设置",
+        ),
+    }
+}
+
+/// 执行环境自检
+#[tauri::command]
+pub async fn run_self_check(network: Option<bool>) -> Result<SelfCheckReport, String> {
+    Ok(self_check(network.unwrap_or(false)).await)
+}
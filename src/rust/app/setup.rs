@@ -1,7 +1,8 @@
 use crate::config::{AppState, load_config_and_apply_window_settings};
-use crate::ui::{initialize_audio_asset_manager, setup_window_event_listeners};
+use crate::mcp::types::PopupRequest;
+use crate::ui::{apply_focus_policy, initialize_audio_asset_manager, mark_request_pending, setup_global_shortcut, setup_window_event_listeners};
 use crate::ui::exit_handler::setup_exit_handlers;
-use crate::log_important;
+use crate::{log_important, log_with_trace};
 use tauri::{AppHandle, Manager};
 
 /// 应用设置和初始化
@@ -13,14 +14,30 @@ pub async fn setup_application(app_handle: &AppHandle) -> Result<(), String> {
         log_important!(warn, "加载配置失败: {}", e);
     }
 
+    // MCP请求模式下按焦点策略处理窗口
+    if let Some(request) = load_cli_popup_request() {
+        log_with_trace!(info, request.trace_label(), "等一下已加载请求: id={}", request.id);
+        crate::app::mark_ui_launched();
+        mark_request_pending(app_handle, &request.id);
+        if let Err(e) = apply_focus_policy(app_handle, request.urgent) {
+            log_important!(warn, "应用焦点策略失败: {}", e);
+        }
+    }
+
     // 初始化音频资源管理器
     if let Err(e) = initialize_audio_asset_manager(app_handle) {
         log_important!(warn, "初始化音频资源管理器失败: {}", e);
     }
 
+    // 注册全局快捷键
+    setup_global_shortcut(app_handle);
+
     // 设置窗口事件监听器
     setup_window_event_listeners(app_handle);
 
+    // 收到 SIGINT/SIGTERM 时走正常退出流程
+    crate::ui::setup_signal_handlers(app_handle);
+
     // 设置退出处理器
     if let Err(e) = setup_exit_handlers(app_handle) {
         log_important!(warn, "设置退出处理器失败: {}", e);
@@ -28,3 +45,18 @@ pub async fn setup_application(app_handle: &AppHandle) -> Result<(), String> {
 
     Ok(())
 }
+
+/// 读取命令行传入的MCP请求（非MCP模式时返回None）
+fn load_cli_popup_request() -> Option<PopupRequest> {
+    let request_file = crate::app::mcp_request_file()?;
+
+    match std::fs::read_to_string(&request_file) {
+        Ok(content) => serde_json::from_str(&content)
+            .map_err(|e| log_important!(warn, "解析MCP请求失败: {}", e))
+            .ok(),
+        Err(e) => {
+            log_important!(warn, "读取MCP请求文件失败: {}", e);
+            None
+        }
+    }
+}
@@ -1,11 +1,56 @@
 // MCP 服务器入口点
-use cunzhi::{mcp::run_server, utils::auto_init_logger, log_important};
+use cunzhi::{
+    app::{
+        apply_global_options, parse_server_cli, print_version, run_self_check_cli, run_send_test_popup,
+        run_validate_config, CliCommand,
+    },
+    config::config_path_override,
+    mcp::run_server,
+    utils::{auto_init_logger, cleanup_stale_runtime_files},
+    log_important,
+};
 
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    // 解析命令行参数（全局选项需在日志初始化前生效）
+    let cli = parse_server_cli();
+    apply_global_options(&cli)?;
+
+    if cli.version {
+        print_version();
+        return Ok(());
+    }
+
     // 自动初始化日志系统
     auto_init_logger()?;
 
-    log_important!(info, "启动 MCP 服务器");
-    run_server().await
+    if let Some(config_path) = config_path_override() {
+        log_important!(info, "使用配置文件: {}", config_path.display());
+    }
+
+    match cli.command {
+        // 配置检查模式：检查后退出
+        Some(CliCommand::ValidateConfig { path }) => {
+            std::process::exit(run_validate_config(path, cli.json));
+        }
+        // 测试弹窗模式：发送一次弹窗后退出
+        Some(CliCommand::SendTestPopup { message, options, timeout }) => {
+            std::process::exit(run_send_test_popup(message, options, timeout).await);
+        }
+        // 自检模式：检查本地运行环境后退出
+        Some(CliCommand::SelfCheck { network }) => {
+            std::process::exit(run_self_check_cli(network, cli.json).await);
+        }
+        Some(command) => {
+            eprintln!("寸止 不支持该命令: {:?}", command);
+            std::process::exit(1);
+        }
+        None => {
+            log_important!(info, "启动 MCP 服务器");
+            if let Err(e) = cleanup_stale_runtime_files() {
+                log_important!(warn, "清理残留请求文件失败: {}", e);
+            }
+            run_server().await
+        }
+    }
 }
@@ -1,5 +1,7 @@
 pub mod settings;
 pub mod storage;
+pub mod validate;
 
 pub use settings::*;
 pub use storage::*;
+pub use validate::*;
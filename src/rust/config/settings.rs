@@ -1,7 +1,7 @@
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::sync::Mutex;
-use crate::constants::{window, theme, audio, mcp, telegram, font};
+use crate::constants::{app, window, theme, audio, mcp, telegram, font};
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct AppConfig {
@@ -19,6 +19,16 @@ pub struct AppConfig {
     pub custom_prompt_config: CustomPromptConfig, // 自定义prompt配置
     #[serde(default = "default_shortcut_config")]
     pub shortcut_config: ShortcutConfig, // 自定义快捷键配置
+    #[serde(default)]
+    pub webhooks: Vec<WebhookConfig>, // 弹窗响应回调配置
+    #[serde(default = "default_quiet_hours_config")]
+    pub quiet_hours: QuietHoursConfig, // 免打扰时段配置
+    #[serde(default)]
+    pub popup_templates: HashMap<String, PopupTemplate>, // 弹窗模板（名称 -> 模板）
+    #[serde(default = "default_dnd_config")]
+    pub dnd: DndConfig, // 勿扰模式（手动开关）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub runtime_dir: Option<String>, // 临时文件目录，为空时使用系统缓存目录下的 cunzhi 子目录
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -38,6 +48,22 @@ pub struct UiConfig {
     // 置顶设置
     #[serde(default = "default_always_on_top")]
     pub always_on_top: bool,
+
+    // 收到请求时发送系统通知
+    #[serde(default = "default_notify_on_request")]
+    pub notify_on_request: bool,
+
+    // 收到请求时的窗口焦点策略
+    #[serde(default = "default_focus_policy")]
+    pub focus_policy: String, // "Always", "Never", "OnlyWhenUrgent"
+
+    // 唤起待处理弹窗的全局快捷键（空字符串表示不启用）
+    #[serde(default = "default_global_shortcut")]
+    pub global_shortcut: String, // 如 "Ctrl+Alt+Space"
+
+    // 界面语言（可被 CUNZHI_LANG 环境变量覆盖）
+    #[serde(default = "default_language")]
+    pub language: String, // "zh-CN", "en"
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -84,6 +110,14 @@ pub struct WindowConfig {
     pub free_width: f64,
     #[serde(default = "default_free_height")]
     pub free_height: f64,
+
+    // 窗口位置（物理坐标），未保存时居中显示
+    #[serde(default)]
+    pub x: Option<i32>,
+    #[serde(default)]
+    pub y: Option<i32>,
+    #[serde(default)]
+    pub monitor_name: Option<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -114,6 +148,10 @@ pub struct McpConfig {
     pub acemcp_max_lines_per_blob: Option<u32>, // acemcp最大行数/块
     pub acemcp_text_extensions: Option<Vec<String>>, // acemcp文件扩展名
     pub acemcp_exclude_patterns: Option<Vec<String>>, // acemcp排除模式
+    #[serde(default)]
+    pub expiry_clock_skew_ms: Option<u64>, // 请求过期判断的时钟偏差容忍(ms)
+    #[serde(default)]
+    pub remember_ttl_secs: Option<u64>, // 记住的回答有效期(秒)
 }
 
 // 自定义prompt结构
@@ -176,6 +214,58 @@ pub struct ShortcutKey {
     pub meta: bool, // macOS的Cmd键
 }
 
+// 免打扰时段配置
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct QuietHoursConfig {
+    #[serde(default)]
+    pub enabled: bool,
+    #[serde(default = "default_quiet_hours_start")]
+    pub start: String, // 本地时间 "HH:MM"
+    #[serde(default = "default_quiet_hours_end")]
+    pub end: String, // 本地时间 "HH:MM"，早于开始时间表示跨夜
+    #[serde(default = "default_quiet_hours_days")]
+    pub days: Vec<u32>, // 生效的星期（按开始时间计算，1=周一 ... 7=周日）
+    #[serde(default = "default_quiet_hours_behavior")]
+    pub behavior: String, // "defer", "telegram_only", "auto_respond"
+    #[serde(default)]
+    pub auto_response_text: String, // auto_respond 时的回复内容
+    #[serde(default)]
+    pub override_until: Option<i64>, // 在此时间前忽略免打扰（Unix毫秒时间戳）
+}
+
+// 勿扰模式配置
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct DndConfig {
+    #[serde(default)]
+    pub enabled: bool,
+    #[serde(default = "default_dnd_behavior")]
+    pub behavior: String, // "defer", "reject"
+    #[serde(default = "default_dnd_reject_text")]
+    pub reject_text: String, // reject 时的回复内容
+}
+
+// 弹窗模板配置
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct PopupTemplate {
+    pub message: String,
+    #[serde(default)]
+    pub predefined_options: Vec<String>,
+    #[serde(default = "default_template_allow_multiple")]
+    pub allow_multiple: bool, // 是否允许选择多个选项
+    #[serde(default)]
+    pub timeout_secs: Option<u64>, // 请求有效期(秒)，超时后不再显示弹窗
+}
+
+// 弹窗响应回调配置
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct WebhookConfig {
+    pub url: String,
+    #[serde(default)]
+    pub secret: String, // 用于 X-Cunzhi-Signature 签名的密钥
+    #[serde(default)]
+    pub events: Vec<String>, // 订阅的事件，为空时订阅全部
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct TelegramConfig {
     #[serde(default = "default_telegram_enabled")]
@@ -197,6 +287,10 @@ pub struct AppState {
     // 防误触退出机制
     pub exit_attempt_count: Mutex<u32>,
     pub last_exit_attempt: Mutex<Option<std::time::Instant>>,
+    // 全局快捷键注册错误（用于设置界面显示）
+    pub global_shortcut_error: Mutex<Option<String>>,
+    // 所有寸止进程待处理弹窗数量的最近一次统计结果
+    pub pending_request_count: Mutex<u32>,
 }
 
 impl Default for AppConfig {
@@ -209,6 +303,11 @@ impl Default for AppConfig {
             telegram_config: default_telegram_config(),
             custom_prompt_config: default_custom_prompt_config(),
             shortcut_config: default_shortcut_config(),
+            webhooks: Vec::new(),
+            quiet_hours: default_quiet_hours_config(),
+            popup_templates: HashMap::new(),
+            dnd: default_dnd_config(),
+            runtime_dir: None,
         }
     }
 }
@@ -220,6 +319,8 @@ impl Default for AppState {
             response_channel: Mutex::new(None),
             exit_attempt_count: Mutex::new(0),
             last_exit_attempt: Mutex::new(None),
+            global_shortcut_error: Mutex::new(None),
+            pending_request_count: Mutex::new(0),
         }
     }
 }
@@ -231,6 +332,10 @@ pub fn default_ui_config() -> UiConfig {
         font_config: default_font_config(),
         window_config: default_window_config(),
         always_on_top: default_always_on_top(),
+        notify_on_request: default_notify_on_request(),
+        focus_policy: default_focus_policy(),
+        global_shortcut: default_global_shortcut(),
+        language: default_language(),
     }
 }
 
@@ -250,9 +355,59 @@ pub fn default_mcp_config() -> McpConfig {
         acemcp_max_lines_per_blob: None,
         acemcp_text_extensions: None,
         acemcp_exclude_patterns: None,
+        expiry_clock_skew_ms: None,
+        remember_ttl_secs: None,
+    }
+}
+
+pub fn default_quiet_hours_config() -> QuietHoursConfig {
+    QuietHoursConfig {
+        enabled: false,
+        start: default_quiet_hours_start(),
+        end: default_quiet_hours_end(),
+        days: default_quiet_hours_days(),
+        behavior: default_quiet_hours_behavior(),
+        auto_response_text: String::new(),
+        override_until: None,
+    }
+}
+
+pub fn default_dnd_config() -> DndConfig {
+    DndConfig {
+        enabled: false,
+        behavior: default_dnd_behavior(),
+        reject_text: default_dnd_reject_text(),
     }
 }
 
+pub fn default_dnd_behavior() -> String {
+    mcp::DND_DEFER.to_string()
+}
+
+pub fn default_dnd_reject_text() -> String {
+    mcp::DEFAULT_DND_REJECT_TEXT.to_string()
+}
+
+pub fn default_template_allow_multiple() -> bool {
+    true
+}
+
+pub fn default_quiet_hours_start() -> String {
+    mcp::DEFAULT_QUIET_HOURS_START.to_string()
+}
+
+pub fn default_quiet_hours_end() -> String {
+    mcp::DEFAULT_QUIET_HOURS_END.to_string()
+}
+
+pub fn default_quiet_hours_days() -> Vec<u32> {
+    (1..=7).collect()
+}
+
+pub fn default_quiet_hours_behavior() -> String {
+    mcp::QUIET_HOURS_DEFER.to_string()
+}
+
 pub fn default_telegram_config() -> TelegramConfig {
     TelegramConfig {
         enabled: default_telegram_enabled(),
@@ -275,6 +430,22 @@ pub fn default_always_on_top() -> bool {
     window::DEFAULT_ALWAYS_ON_TOP
 }
 
+pub fn default_notify_on_request() -> bool {
+    window::DEFAULT_NOTIFY_ON_REQUEST
+}
+
+pub fn default_focus_policy() -> String {
+    window::DEFAULT_FOCUS_POLICY.to_string()
+}
+
+pub fn default_global_shortcut() -> String {
+    window::DEFAULT_GLOBAL_SHORTCUT.to_string()
+}
+
+pub fn default_language() -> String {
+    app::DEFAULT_LANGUAGE.to_string()
+}
+
 pub fn default_audio_notification_enabled() -> bool {
     audio::DEFAULT_NOTIFICATION_ENABLED
 }
@@ -299,6 +470,9 @@ pub fn default_window_config() -> WindowConfig {
         fixed_height: window::DEFAULT_HEIGHT,
         free_width: window::DEFAULT_WIDTH,
         free_height: window::DEFAULT_HEIGHT,
+        x: None,
+        y: None,
+        monitor_name: None,
     }
 }
 
@@ -417,6 +591,20 @@ impl WindowConfig {
         }
     }
 
+    // 更新窗口位置
+    pub fn update_position(&mut self, x: i32, y: i32, monitor_name: Option<String>) {
+        self.x = Some(x);
+        self.y = Some(y);
+        self.monitor_name = monitor_name;
+    }
+
+    // 清除保存的窗口位置
+    pub fn clear_position(&mut self) {
+        self.x = None;
+        self.y = None;
+        self.monitor_name = None;
+    }
+
     // 更新当前模式的尺寸
     pub fn update_current_size(&mut self, width: f64, height: f64) {
         if self.fixed {
@@ -1,9 +1,41 @@
 use anyhow::Result;
 use std::fs;
-use std::path::PathBuf;
-use tauri::{AppHandle, LogicalSize, Manager, State};
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+use tauri::{AppHandle, LogicalSize, Manager, PhysicalPosition, State, WebviewWindow};
 
-use super::settings::{AppConfig, AppState, default_shortcuts};
+use super::settings::{AppConfig, AppState, WindowConfig, default_shortcuts};
+use crate::constants::app;
+
+/// 命令行指定的配置文件路径（需在初始化前设置）
+static CONFIG_PATH_OVERRIDE: OnceLock<PathBuf> = OnceLock::new();
+
+/// 将相对路径解析为基于当前工作目录的绝对路径
+fn resolve_path(path: &Path) -> PathBuf {
+    if path.is_absolute() {
+        path.to_path_buf()
+    } else {
+        std::env::current_dir()
+            .unwrap_or_else(|_| PathBuf::from("."))
+            .join(path)
+    }
+}
+
+/// 设置配置文件路径覆盖（`--config` 参数），只能设置一次
+pub fn set_config_path_override(path: &Path) -> Result<()> {
+    CONFIG_PATH_OVERRIDE
+        .set(resolve_path(path))
+        .map_err(|_| anyhow::anyhow!("配置文件路径已设置"))
+}
+
+/// 获取配置文件路径覆盖，命令行参数优先于环境变量
+pub fn config_path_override() -> Option<PathBuf> {
+    CONFIG_PATH_OVERRIDE.get().cloned().or_else(|| {
+        std::env::var_os(app::CONFIG_PATH_ENV)
+            .filter(|value| !value.is_empty())
+            .map(|value| resolve_path(Path::new(&value)))
+    })
+}
 
 pub fn get_config_path(_app: &AppHandle) -> Result<PathBuf> {
     // 使用与独立配置相同的路径，确保一致性
@@ -114,11 +146,44 @@ pub async fn load_config_and_apply_window_settings(
         if let Err(_e) = window.set_size(LogicalSize::new(target_width, target_height)) {
             // 静默处理窗口大小设置失败
         }
+
+        // 恢复窗口位置
+        apply_saved_window_position(&window, &window_config);
     }
 
     Ok(())
 }
 
+/// 恢复保存的窗口位置
+///
+/// 保存的位置不在任何当前显示器范围内时回退为居中显示
+fn apply_saved_window_position(window: &WebviewWindow, window_config: &WindowConfig) {
+    let (Some(x), Some(y)) = (window_config.x, window_config.y) else {
+        return;
+    };
+
+    let monitors = window.available_monitors().unwrap_or_default();
+    let is_visible = monitors.iter().any(|monitor| {
+        let position = monitor.position();
+        let size = monitor.size();
+        x >= position.x
+            && y >= position.y
+            && x < position.x + size.width as i32
+            && y < position.y + size.height as i32
+    });
+
+    if is_visible {
+        if let Err(e) = window.set_position(PhysicalPosition::new(x, y)) {
+            log::warn!("恢复窗口位置失败: {}", e);
+        } else {
+            log::debug!("窗口位置已恢复: ({}, {}) {:?}", x, y, window_config.monitor_name);
+        }
+    } else {
+        log::info!("保存的窗口位置 ({}, {}) 不在当前显示器范围内，窗口居中显示", x, y);
+        let _ = window.center();
+    }
+}
+
 /// 独立加载配置文件（用于MCP服务器等独立进程）
 pub fn load_standalone_config() -> Result<AppConfig> {
     let config_path = get_standalone_config_path()?;
@@ -144,7 +209,15 @@ pub fn load_standalone_telegram_config() -> Result<super::settings::TelegramConf
 }
 
 /// 获取独立配置文件路径（不依赖Tauri）
-fn get_standalone_config_path() -> Result<PathBuf> {
+pub fn get_standalone_config_path() -> Result<PathBuf> {
+    // 优先使用 --config 参数或环境变量指定的路径
+    if let Some(config_path) = config_path_override() {
+        if let Some(parent) = config_path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        return Ok(config_path);
+    }
+
     // 使用标准的配置目录
     let config_dir = dirs::config_dir()
         .ok_or_else(|| anyhow::anyhow!("无法获取配置目录"))?
@@ -153,7 +226,7 @@ fn get_standalone_config_path() -> Result<PathBuf> {
     // 确保目录存在
     fs::create_dir_all(&config_dir)?;
 
-    Ok(config_dir.join("config.json"))
+    Ok(config_dir.join(app::CONFIG_FILE_NAME))
 }
 
 /// 合并默认快捷键配置，确保新的默认快捷键被添加到现有配置中
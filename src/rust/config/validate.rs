@@ -0,0 +1,128 @@
+use serde::Serialize;
+use std::fs;
+use std::path::Path;
+
+use super::settings::AppConfig;
+use crate::constants::{validation, window};
+
+/// 配置检查发现的问题
+#[derive(Debug, Clone, Serialize)]
+pub struct ConfigFinding {
+    pub severity: String, // "error", "warning"
+    pub path: String,     // 配置项路径，如 "ui_config.theme"
+    pub message: String,
+}
+
+impl ConfigFinding {
+    fn error(path: &str, message: String) -> Self {
+        Self { severity: "error".to_string(), path: path.to_string(), message }
+    }
+
+    fn warning(path: &str, message: String) -> Self {
+        Self { severity: "warning".to_string(), path: path.to_string(), message }
+    }
+
+    pub fn is_error(&self) -> bool {
+        self.severity == "error"
+    }
+}
+
+/// 检查配置文件（只读取本地文件，不进行任何网络请求）
+pub fn validate_config_file(config_path: &Path) -> Vec<ConfigFinding> {
+    let content = match fs::read_to_string(config_path) {
+        Ok(content) => content,
+        Err(e) => {
+            return vec![ConfigFinding::error("", format!("读取配置文件失败: {}", e))];
+        }
+    };
+
+    let value: serde_json::Value = match serde_json::from_str(&content) {
+        Ok(value) => value,
+        Err(e) => {
+            return vec![ConfigFinding::error("", format!("配置文件不是有效的JSON: {}", e))];
+        }
+    };
+
+    match serde_json::from_value::<AppConfig>(value) {
+        Ok(config) => validate_config(&config),
+        Err(e) => vec![ConfigFinding::error("", format!("配置文件结构无效: {}", e))],
+    }
+}
+
+/// 检查已解析的配置
+pub fn validate_config(config: &AppConfig) -> Vec<ConfigFinding> {
+    let mut findings = Vec::new();
+
+    if !validation::is_valid_theme(&config.ui_config.theme) {
+        findings.push(ConfigFinding::warning(
+            "ui_config.theme",
+            format!("未知的主题: {}", config.ui_config.theme),
+        ));
+    }
+
+    let focus_policies = [
+        window::FOCUS_POLICY_ALWAYS,
+        window::FOCUS_POLICY_NEVER,
+        window::FOCUS_POLICY_ONLY_WHEN_URGENT,
+    ];
+    if !focus_policies.contains(&config.ui_config.focus_policy.as_str()) {
+        findings.push(ConfigFinding::error(
+            "ui_config.focus_policy",
+            format!("无效的焦点策略: {}", config.ui_config.focus_policy),
+        ));
+    }
+
+    let window_config = &config.ui_config.window_config;
+    let (width, height) = if window_config.fixed {
+        (window_config.fixed_width, window_config.fixed_height)
+    } else {
+        (window_config.free_width, window_config.free_height)
+    };
+    let window_result = validation::validate_window_config(width, height, window_config.x, window_config.y);
+    for error in window_result.errors {
+        findings.push(ConfigFinding::error("ui_config.window_config", error));
+    }
+
+    let audio_config = &config.audio_config;
+    let audio_result = validation::validate_audio_config(&audio_config.custom_url, 1.0, audio_config.notification_enabled);
+    for error in audio_result.errors {
+        findings.push(ConfigFinding::error("audio_config.custom_url", error));
+    }
+    if let Some(local_path) = audio_config.custom_url.strip_prefix("file://") {
+        if !Path::new(local_path).exists() {
+            findings.push(ConfigFinding::error(
+                "audio_config.custom_url",
+                format!("音频文件不存在: {}", local_path),
+            ));
+        }
+    }
+
+    let telegram_config = &config.telegram_config;
+    if telegram_config.enabled {
+        if !validation::is_valid_bot_token(&telegram_config.bot_token) {
+            findings.push(ConfigFinding::error("telegram_config.bot_token", "无效的 Bot Token".to_string()));
+        }
+        if !validation::is_valid_chat_id(&telegram_config.chat_id) {
+            findings.push(ConfigFinding::error(
+                "telegram_config.chat_id",
+                format!("无效的 Chat ID: {}", telegram_config.chat_id),
+            ));
+        }
+    } else if telegram_config.hide_frontend_popup {
+        findings.push(ConfigFinding::warning(
+            "telegram_config.hide_frontend_popup",
+            "Telegram 未启用，隐藏前端弹窗的设置不会生效".to_string(),
+        ));
+    }
+
+    for (index, webhook) in config.webhooks.iter().enumerate() {
+        if webhook.url.is_empty() || !validation::is_valid_url(&webhook.url) || webhook.url.starts_with("file://") {
+            findings.push(ConfigFinding::error(
+                &format!("webhooks[{}].url", index),
+                format!("无效的 webhook 地址: {}", webhook.url),
+            ));
+        }
+    }
+
+    findings
+}
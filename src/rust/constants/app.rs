@@ -12,6 +12,18 @@ pub const DESCRIPTION: &str = "智能代码审查工具";
 /// 应用程序版本（从 Cargo.toml 获取）
 pub const VERSION: &str = env!("CARGO_PKG_VERSION");
 
+/// 构建时的 git 提交（短哈希）
+pub const GIT_HASH: &str = env!("CUNZHI_GIT_HASH");
+
+/// 构建时间（Unix 秒）
+pub const BUILD_TIMESTAMP: &str = env!("CUNZHI_BUILD_TIMESTAMP");
+
+/// 构建目标平台
+pub const BUILD_TARGET: &str = env!("CUNZHI_BUILD_TARGET");
+
+/// 构建使用的 rustc 版本
+pub const RUSTC_VERSION: &str = env!("CUNZHI_RUSTC_VERSION");
+
 /// 应用程序作者
 pub const AUTHOR: &str = "imhuso";
 
@@ -27,6 +39,45 @@ pub const LICENSE: &str = "MIT";
 /// 配置文件名
 pub const CONFIG_FILE_NAME: &str = "config.json";
 
+/// 记住的回答文件名（与配置文件同目录）
+pub const REMEMBERED_ANSWERS_FILE_NAME: &str = "remembered_answers.json";
+
+/// 回复草稿文件名（与配置文件同目录）
+pub const RESPONSE_DRAFTS_FILE_NAME: &str = "response_drafts.json";
+
+/// 弹窗耗时样本文件名（与配置文件同目录）
+pub const LATENCY_SAMPLES_FILE_NAME: &str = "latency_samples.json";
+
+/// 覆盖配置文件路径的环境变量
+pub const CONFIG_PATH_ENV: &str = "CUNZHI_CONFIG_PATH";
+
+/// 覆盖运行时目录的环境变量
+pub const RUNTIME_DIR_ENV: &str = "CUNZHI_RUNTIME_DIR";
+
+/// 运行时目录在系统缓存目录下的子目录名
+pub const RUNTIME_DIR_NAME: &str = "cunzhi";
+
+/// 运行时目录中请求文件的文件名前缀
+pub const REQUEST_FILE_PREFIX: &str = "mcp_request_";
+
+/// 运行时目录中待处理弹窗标记文件的文件名前缀
+pub const PENDING_MARKER_PREFIX: &str = "pending_popup_";
+
+/// 运行时目录中全局快捷键所有权锁文件名，持有锁的等一下进程负责注册快捷键
+pub const GLOBAL_SHORTCUT_LOCK_FILE: &str = "global_shortcut.lock";
+
+/// 运行时目录中聚焦请求文件名，内容为需要唤起的请求ID
+pub const FOCUS_REQUEST_FILE: &str = "focus_request";
+
+/// 运行时目录中请求文件和标记文件的保留时间（秒），超过后启动时清理
+pub const STALE_RUNTIME_FILE_SECS: u64 = 24 * 60 * 60;
+
+/// 覆盖界面语言的环境变量（如 "en"、"zh-CN"）
+pub const LANG_ENV: &str = "CUNZHI_LANG";
+
+/// 默认界面语言
+pub const DEFAULT_LANGUAGE: &str = "zh-CN";
+
 /// 日志文件名前缀
 pub const LOG_FILE_PREFIX: &str = "cunzhi";
 
@@ -43,6 +94,9 @@ pub const EXIT_CONFIRMATION_WINDOW_SECS: u64 = 3;
 /// 需要的连续退出尝试次数
 pub const REQUIRED_EXIT_ATTEMPTS: u32 = 2;
 
+/// 退出清理的最长等待时间 (ms)，超时后强制退出
+pub const SHUTDOWN_DEADLINE_MS: u64 = 5000;
+
 // 应用程序信息结构体
 #[derive(Debug, Clone)]
 pub struct AppInfo {
@@ -103,7 +157,48 @@ impl AppInfo {
     }
 }
 
+// 构建信息结构体
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct BuildInfo {
+    pub version: String,
+    pub git_hash: String,
+    pub build_time: String, // RFC3339 格式
+    pub target: String,
+    pub rustc_version: String,
+}
+
+impl Default for BuildInfo {
+    fn default() -> Self {
+        let build_time = BUILD_TIMESTAMP
+            .parse::<i64>()
+            .ok()
+            .and_then(|secs| chrono::DateTime::from_timestamp(secs, 0))
+            .map(|time| time.to_rfc3339())
+            .unwrap_or_else(|| "unknown".to_string());
+
+        Self {
+            version: VERSION.to_string(),
+            git_hash: GIT_HASH.to_string(),
+            build_time,
+            target: BUILD_TARGET.to_string(),
+            rustc_version: RUSTC_VERSION.to_string(),
+        }
+    }
+}
+
+impl BuildInfo {
+    /// 带提交哈希的版本号，如 "0.4.0 (a1b2c3d)"
+    pub fn version_with_hash(&self) -> String {
+        format!("{} ({})", self.version, self.git_hash)
+    }
+}
+
 // 便捷函数
+/// 获取构建信息
+pub fn get_build_info() -> BuildInfo {
+    BuildInfo::default()
+}
+
 /// 获取应用程序信息
 pub fn get_app_info() -> AppInfo {
     AppInfo::default()
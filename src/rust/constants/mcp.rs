@@ -27,6 +27,96 @@ pub const REQUEST_TIMEOUT_MS: u64 = 30000;
 /// MCP 重试次数
 pub const MAX_RETRY_COUNT: u32 = 3;
 
+/// 请求过期判断的默认时钟偏差容忍 (ms)
+pub const DEFAULT_EXPIRY_CLOCK_SKEW_MS: u64 = 5000;
+
+/// 请求过期时返回给调用方的响应
+pub const EXPIRED_RESPONSE: &str = "EXPIRED";
+
+/// 输入约束正则表达式的最大长度
+pub const INPUT_PATTERN_MAX_LEN: usize = 512;
+
+/// 输入约束正则表达式编译后的大小上限（防止调用方提供过于复杂的正则）
+pub const INPUT_PATTERN_SIZE_LIMIT: usize = 256 * 1024;
+
+/// 用户响应不满足输入约束时重新弹窗的次数
+pub const INVALID_RESPONSE_REPROMPTS: u32 = 1;
+
+/// 弹窗附件类型：图片
+pub const ATTACHMENT_KIND_IMAGE: &str = "image";
+
+/// 弹窗附件类型：文件（仅用于用户回答）
+pub const ATTACHMENT_KIND_FILE: &str = "file";
+
+/// 支持的弹窗附件 MIME 类型
+pub const SUPPORTED_ATTACHMENT_MIME_TYPES: &[&str] = &["image/png", "image/jpeg", "image/gif", "image/webp"];
+
+/// 单个弹窗附件大小上限 (字节，解码后)
+pub const ATTACHMENT_MAX_BYTES: usize = 5 * 1024 * 1024;
+
+/// 单个请求所有附件的总大小上限 (字节，解码后)
+pub const ATTACHMENTS_TOTAL_MAX_BYTES: usize = 10 * 1024 * 1024;
+
+/// 记住的回答默认有效期 (秒)
+pub const DEFAULT_REMEMBER_TTL_SECS: u64 = 7 * 24 * 60 * 60;
+
+/// 未提交的回复草稿保留时间 (秒)
+pub const RESPONSE_DRAFT_TTL_SECS: u64 = 24 * 60 * 60;
+
+/// 保留用于耗时统计的最近弹窗样本数量
+pub const LATENCY_SAMPLE_LIMIT: usize = 200;
+
+/// 免打扰默认开始时间
+pub const DEFAULT_QUIET_HOURS_START: &str = "23:00";
+
+/// 免打扰默认结束时间
+pub const DEFAULT_QUIET_HOURS_END: &str = "07:00";
+
+/// 免打扰行为：暂缓弹窗直到免打扰结束
+pub const QUIET_HOURS_DEFER: &str = "defer";
+
+/// 免打扰行为：仅通过 Telegram 交互
+pub const QUIET_HOURS_TELEGRAM_ONLY: &str = "telegram_only";
+
+/// 免打扰行为：使用预设文本自动回复
+pub const QUIET_HOURS_AUTO_RESPOND: &str = "auto_respond";
+
+/// 免打扰自动回复的响应来源标识
+pub const QUIET_HOURS_AUTO_SOURCE: &str = "quiet_hours_auto";
+
+/// 勿扰模式行为：暂缓弹窗直到关闭勿扰（请求过期时返回过期响应）
+pub const DND_DEFER: &str = "defer";
+
+/// 勿扰模式行为：直接使用预设文本回复
+pub const DND_REJECT: &str = "reject";
+
+/// 勿扰模式默认回复内容
+pub const DEFAULT_DND_REJECT_TEXT: &str = "用户当前处于勿扰模式，暂时无法回复。请自行判断是否继续，或稍后再询问。";
+
+/// 勿扰模式自动回复的响应来源标识
+pub const DND_AUTO_SOURCE: &str = "dnd_auto";
+
+/// 暂缓弹窗时重新检查免打扰状态的间隔 (秒)
+pub const QUIET_HOURS_RECHECK_SECS: u64 = 60;
+
+/// `--mcp-request` 退出码：用户已回答
+pub const MCP_REQUEST_EXIT_ANSWERED: i32 = 0;
+
+/// `--mcp-request` 退出码：用户取消
+pub const MCP_REQUEST_EXIT_CANCELLED: i32 = 2;
+
+/// `--mcp-request` 退出码：请求超时或已过期
+pub const MCP_REQUEST_EXIT_TIMEOUT: i32 = 3;
+
+/// `--mcp-request` 退出码：内部错误
+pub const MCP_REQUEST_EXIT_ERROR: i32 = 4;
+
+/// `--mcp-request` 退出码：标准输入中的请求无效
+pub const MCP_REQUEST_EXIT_INVALID_INPUT: i32 = 5;
+
+/// 等一下启动失败时保留的标准错误输出行数
+pub const LAUNCH_STDERR_TAIL_LINES: usize = 20;
+
 // MCP 工具配置结构体
 #[derive(Debug, Clone)]
 pub struct McpToolConfig {
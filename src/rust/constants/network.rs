@@ -30,6 +30,18 @@ pub const WRITE_TIMEOUT_MS: u64 = 10000;
 /// 最大并发连接数
 pub const MAX_CONCURRENT_CONNECTIONS: usize = 10;
 
+/// webhook 首次发送失败后的最大重试次数（最多发送 1 + 3 次）
+pub const WEBHOOK_RETRY_COUNT: u32 = 3;
+
+/// webhook 重试基础间隔 (ms)，每次重试翻倍
+pub const WEBHOOK_RETRY_BASE_DELAY_MS: u64 = 1000;
+
+/// webhook 单次请求超时时间 (ms)
+pub const WEBHOOK_TIMEOUT_MS: u64 = 10000;
+
+/// 启用指标监听的环境变量（值为监听地址，如 127.0.0.1:9464）
+pub const METRICS_ADDR_ENV: &str = "CUNZHI_METRICS_ADDR";
+
 /// 默认用户代理
 pub const DEFAULT_USER_AGENT: &str = concat!(env!("CARGO_PKG_NAME"), "/", env!("CARGO_PKG_VERSION"));
 
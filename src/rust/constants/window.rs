@@ -27,6 +27,30 @@ pub const DEFAULT_FIXED_MODE: bool = false;
 /// 默认自动调整大小状态
 pub const DEFAULT_AUTO_RESIZE: bool = true;
 
+/// 窗口移动后保存位置的防抖时间 (ms)
+pub const POSITION_SAVE_DEBOUNCE_MS: u64 = 500;
+
+/// 默认收到请求时发送系统通知状态
+pub const DEFAULT_NOTIFY_ON_REQUEST: bool = false;
+
+/// 焦点策略：总是抢占焦点
+pub const FOCUS_POLICY_ALWAYS: &str = "Always";
+
+/// 焦点策略：从不抢占焦点
+pub const FOCUS_POLICY_NEVER: &str = "Never";
+
+/// 焦点策略：仅紧急请求抢占焦点
+pub const FOCUS_POLICY_ONLY_WHEN_URGENT: &str = "OnlyWhenUrgent";
+
+/// 默认窗口焦点策略
+pub const DEFAULT_FOCUS_POLICY: &str = FOCUS_POLICY_ONLY_WHEN_URGENT;
+
+/// 默认全局快捷键（空字符串表示不启用）
+pub const DEFAULT_GLOBAL_SHORTCUT: &str = "";
+
+/// 系统通知中消息预览的最大字符数
+pub const NOTIFICATION_PREVIEW_MAX_CHARS: usize = 120;
+
 // 窗口约束结构体
 #[derive(Debug, Clone)]
 pub struct WindowConstraints {
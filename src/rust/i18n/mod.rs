@@ -0,0 +1,181 @@
+// 界面文本本地化
+// 面向用户的文本通过消息键获取，日志文本保持中文便于检索
+
+use std::fmt::Display;
+use std::sync::OnceLock;
+
+use crate::config::load_standalone_config;
+use crate::constants::app;
+
+/// 界面语言
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Lang {
+    ZhCn,
+    En,
+}
+
+impl Lang {
+    /// 解析语言代码，支持 "zh-CN"、"zh"、"en"、"en-US" 等形式
+    pub fn parse(code: &str) -> Option<Self> {
+        let code = code.trim().to_lowercase().replace('_', "-");
+        match code.split(['-', '.']).next().unwrap_or("") {
+            "zh" => Some(Lang::ZhCn),
+            "en" => Some(Lang::En),
+            _ => None,
+        }
+    }
+
+    pub fn code(&self) -> &'static str {
+        match self {
+            Lang::ZhCn => "zh-CN",
+            Lang::En => "en",
+        }
+    }
+}
+
+static CURRENT_LANG: OnceLock<Lang> = OnceLock::new();
+
+/// 当前进程使用的语言
+///
+/// 优先使用 `CUNZHI_LANG` 环境变量，其次为配置中的 `ui_config.language`，
+/// 进程内首次调用后缓存，修改配置需重启生效
+pub fn current_lang() -> Lang {
+    *CURRENT_LANG.get_or_init(|| {
+        std::env::var(app::LANG_ENV)
+            .ok()
+            .and_then(|code| Lang::parse(&code))
+            .or_else(|| {
+                load_standalone_config()
+                    .ok()
+                    .and_then(|config| Lang::parse(&config.ui_config.language))
+            })
+            .unwrap_or(Lang::ZhCn)
+    })
+}
+
+/// 消息键
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Msg {
+    UiCommandNotFound,
+    UiProcessFailed,
+    UnknownError,
+    ExitConfirmation,
+    CliConfigFile,
+    CliNoFindings,
+    CliPass,
+    CliWarn,
+    CliFail,
+    CliError,
+    CliHint,
+}
+
+impl Msg {
+    /// 消息模板，`{}` 按顺序替换为参数
+    pub fn template(self, lang: Lang) -> &'static str {
+        match (self, lang) {
+            (Msg::UiCommandNotFound, Lang::ZhCn) => {
+                "找不到等一下 UI 命令。请确保：\n\
+                 1. 已编译项目：cargo build --release\n\
+                 2. 或已全局安装：./install.sh\n\
+                 3. 或等一下命令在同目录下"
+            }
+            (Msg::UiCommandNotFound, Lang::En) => {
+                "Cannot find the 等一下 UI command. Make sure that:\n\
+                 1. the project is built: cargo build --release\n\
+                 2. or it is installed globally: ./install.sh\n\
+                 3. or 等一下 is in the same directory"
+            }
+            (Msg::UiProcessFailed, Lang::ZhCn) => "UI进程失败: {}",
+            (Msg::UiProcessFailed, Lang::En) => "UI process failed: {}",
+            (Msg::UnknownError, Lang::ZhCn) => "未知错误",
+            (Msg::UnknownError, Lang::En) => "unknown error",
+            (Msg::ExitConfirmation, Lang::ZhCn) => "再次按下退出快捷键以确认退出 ({}内有效)",
+            (Msg::ExitConfirmation, Lang::En) => "Press the exit shortcut again within {} to quit",
+            (Msg::CliConfigFile, Lang::ZhCn) => "配置文件: {}",
+            (Msg::CliConfigFile, Lang::En) => "Config file: {}",
+            (Msg::CliNoFindings, Lang::ZhCn) => "未发现问题",
+            (Msg::CliNoFindings, Lang::En) => "No problems found",
+            (Msg::CliPass, Lang::ZhCn) => "通过",
+            (Msg::CliPass, Lang::En) => "PASS",
+            (Msg::CliWarn, Lang::ZhCn) => "警告",
+            (Msg::CliWarn, Lang::En) => "WARN",
+            (Msg::CliFail, Lang::ZhCn) => "失败",
+            (Msg::CliFail, Lang::En) => "FAIL",
+            (Msg::CliError, Lang::ZhCn) => "错误",
+            (Msg::CliError, Lang::En) => "ERROR",
+            (Msg::CliHint, Lang::ZhCn) => "建议: {}",
+            (Msg::CliHint, Lang::En) => "hint: {}",
+        }
+    }
+}
+
+/// 按指定语言填充消息模板
+pub fn tr_in(lang: Lang, msg: Msg, args: &[&dyn Display]) -> String {
+    let mut parts = msg.template(lang).split("{}");
+    let mut text = parts.next().unwrap_or_default().to_string();
+    for (index, part) in parts.enumerate() {
+        if let Some(arg) = args.get(index) {
+            text.push_str(&arg.to_string());
+        }
+        text.push_str(part);
+    }
+    text
+}
+
+/// 按当前语言填充消息模板
+pub fn tr(msg: Msg, args: &[&dyn Display]) -> String {
+    tr_in(current_lang(), msg, args)
+}
+
+/// 按当前语言获取无参数消息
+pub fn t(msg: Msg) -> String {
+    tr(msg, &[])
+}
+
+/// 按指定语言格式化时长，如 "3分钟20秒" / "3m 20s"
+pub fn format_duration_in(lang: Lang, secs: u64) -> String {
+    let (hours, minutes, seconds) = (secs / 3600, secs % 3600 / 60, secs % 60);
+    let units = match lang {
+        Lang::ZhCn => ["小时", "分钟", "秒"],
+        Lang::En => ["h", "m", "s"],
+    };
+    let separator = match lang {
+        Lang::ZhCn => "",
+        Lang::En => " ",
+    };
+
+    let parts: Vec<String> = [hours, minutes, seconds]
+        .iter()
+        .zip(units)
+        .filter(|(value, _)| **value > 0)
+        .map(|(value, unit)| format!("{}{}", value, unit))
+        .collect();
+
+    if parts.is_empty() {
+        format!("0{}", units[2])
+    } else {
+        parts.join(separator)
+    }
+}
+
+/// 按当前语言格式化时长
+pub fn format_duration(secs: u64) -> String {
+    format_duration_in(current_lang(), secs)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_translate_and_format_duration() {
+        assert_eq!(Lang::parse("en_US.UTF-8"), Some(Lang::En));
+        assert_eq!(Lang::parse("zh-CN"), Some(Lang::ZhCn));
+        assert_eq!(Lang::parse("fr"), None);
+
+        assert_eq!(tr_in(Lang::En, Msg::UiProcessFailed, &[&"boom"]), "UI process failed: boom");
+        assert_eq!(format_duration_in(Lang::ZhCn, 200), "3分钟20秒");
+        assert_eq!(format_duration_in(Lang::En, 3605), "1h 5s");
+        assert_eq!(format_duration_in(Lang::En, 0), "0s");
+    }
+}
@@ -1,4 +1,4 @@
-use cunzhi::app::{handle_cli_args, run_tauri_app};
+use cunzhi::app::{apply_global_options, handle_cli_args, parse_cli, prepare_mcp_request, run_tauri_app};
 use cunzhi::utils::auto_init_logger;
 use anyhow::Result;
 
@@ -8,13 +8,16 @@ pub fn run() {
 }
 
 fn main() -> Result<()> {
+    // 解析命令行参数（全局选项需在日志初始化前生效）
+    let cli = parse_cli();
+    apply_global_options(&cli)?;
+    prepare_mcp_request(&cli);
+
     // 初始化日志系统
     if let Err(e) = auto_init_logger() {
         eprintln!("初始化日志系统失败: {}", e);
     }
 
     // 处理命令行参数
-    handle_cli_args()
+    handle_cli_args(cli)
 }
-
-
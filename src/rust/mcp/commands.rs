@@ -133,6 +133,204 @@ pub async fn reset_mcp_tools_config(
     Ok(())
 }
 
+/// 获取免打扰配置
+#[tauri::command]
+pub async fn get_quiet_hours_config(state: State<'_, AppState>) -> Result<crate::config::QuietHoursConfig, String> {
+    let config = state.config.lock().map_err(|e| format!("获取配置失败: {}", e))?;
+    Ok(config.quiet_hours.clone())
+}
+
+/// 保存免打扰配置
+#[tauri::command]
+pub async fn set_quiet_hours_config(
+    quiet_hours: crate::config::QuietHoursConfig,
+    state: State<'_, AppState>,
+    app: AppHandle,
+) -> Result<(), String> {
+    {
+        let mut config = state.config.lock().map_err(|e| format!("获取配置失败: {}", e))?;
+        config.quiet_hours = quiet_hours;
+    }
+
+    // 保存配置到文件
+    save_config(&state, &app).await.map_err(|e| format!("保存配置失败: {}", e))?;
+    Ok(())
+}
+
+/// 获取当前免打扰状态
+#[tauri::command]
+pub async fn get_quiet_hours_state(state: State<'_, AppState>) -> Result<crate::mcp::handlers::QuietHoursState, String> {
+    let config = state.config.lock().map_err(|e| format!("获取配置失败: {}", e))?;
+    Ok(crate::mcp::handlers::quiet_hours_state(&config.quiet_hours))
+}
+
+/// 临时忽略免打扰（忽略今晚的免打扰），直到当前或下一个时段结束
+#[tauri::command]
+pub async fn set_quiet_hours_override(
+    enabled: bool,
+    state: State<'_, AppState>,
+    app: AppHandle,
+) -> Result<crate::mcp::handlers::QuietHoursState, String> {
+    let quiet_hours_state = {
+        let mut config = state.config.lock().map_err(|e| format!("获取配置失败: {}", e))?;
+        config.quiet_hours.override_until = if enabled {
+            crate::mcp::handlers::quiet_hours_override_until(&config.quiet_hours)
+        } else {
+            None
+        };
+        crate::mcp::handlers::quiet_hours_state(&config.quiet_hours)
+    };
+
+    // 保存配置到文件
+    save_config(&state, &app).await.map_err(|e| format!("保存配置失败: {}", e))?;
+    Ok(quiet_hours_state)
+}
+
+/// 获取勿扰模式配置
+#[tauri::command]
+pub async fn get_dnd_config(state: State<'_, AppState>) -> Result<crate::config::DndConfig, String> {
+    let config = state.config.lock().map_err(|e| format!("获取配置失败: {}", e))?;
+    Ok(config.dnd.clone())
+}
+
+/// 开启或关闭勿扰模式，立即对之后的弹窗请求生效
+#[tauri::command]
+pub async fn set_dnd(
+    enabled: bool,
+    behavior: Option<String>,
+    state: State<'_, AppState>,
+    app: AppHandle,
+) -> Result<crate::config::DndConfig, String> {
+    if let Some(behavior) = &behavior {
+        if behavior != mcp::DND_DEFER && behavior != mcp::DND_REJECT {
+            return Err(format!("无效的勿扰行为: {}（可选: {}, {}）", behavior, mcp::DND_DEFER, mcp::DND_REJECT));
+        }
+    }
+
+    let dnd = {
+        let mut config = state.config.lock().map_err(|e| format!("获取配置失败: {}", e))?;
+        config.dnd.enabled = enabled;
+        if let Some(behavior) = behavior {
+            config.dnd.behavior = behavior;
+        }
+        config.dnd.clone()
+    };
+
+    // 保存配置到文件
+    save_config(&state, &app).await.map_err(|e| format!("保存配置失败: {}", e))?;
+    Ok(dnd)
+}
+
+/// 发送测试 webhook 事件
+///
+/// 如果地址已在配置中，使用对应的密钥签名
+#[tauri::command]
+pub async fn test_webhook(url: String, state: State<'_, AppState>) -> Result<String, String> {
+    let webhook = {
+        let config = state.config.lock().map_err(|e| format!("获取配置失败: {}", e))?;
+        config
+            .webhooks
+            .iter()
+            .find(|w| w.url == url)
+            .cloned()
+            .unwrap_or(crate::config::WebhookConfig {
+                url: url.clone(),
+                secret: String::new(),
+                events: Vec::new(),
+            })
+    };
+
+    crate::mcp::handlers::send_test_webhook(&webhook)
+        .await
+        .map_err(|e| format!("webhook 测试失败: {}", e))?;
+
+    Ok(format!("测试事件已发送到 {}", url))
+}
+
+/// 获取最近弹窗各阶段耗时的 p50/p95
+#[tauri::command]
+pub async fn get_latency_stats() -> Result<crate::mcp::handlers::LatencyStats, String> {
+    crate::mcp::handlers::latency_stats().map_err(|e| format!("读取耗时统计失败: {}", e))
+}
+
+/// 获取所有弹窗模板
+#[tauri::command]
+pub async fn list_popup_templates(
+    state: State<'_, AppState>,
+) -> Result<HashMap<String, crate::config::PopupTemplate>, String> {
+    let config = state.config.lock().map_err(|e| format!("获取配置失败: {}", e))?;
+    Ok(config.popup_templates.clone())
+}
+
+/// 新增或更新弹窗模板
+#[tauri::command]
+pub async fn upsert_popup_template(
+    name: String,
+    template: crate::config::PopupTemplate,
+    state: State<'_, AppState>,
+    app: AppHandle,
+) -> Result<(), String> {
+    let name = name.trim().to_string();
+    if name.is_empty() {
+        return Err("模板名称不能为空".to_string());
+    }
+
+    {
+        let mut config = state.config.lock().map_err(|e| format!("获取配置失败: {}", e))?;
+        config.popup_templates.insert(name, template);
+    }
+
+    // 保存配置到文件
+    save_config(&state, &app).await.map_err(|e| format!("保存配置失败: {}", e))?;
+    Ok(())
+}
+
+/// 删除弹窗模板
+#[tauri::command]
+pub async fn delete_popup_template(
+    name: String,
+    state: State<'_, AppState>,
+    app: AppHandle,
+) -> Result<(), String> {
+    {
+        let mut config = state.config.lock().map_err(|e| format!("获取配置失败: {}", e))?;
+        if config.popup_templates.remove(&name).is_none() {
+            return Err(format!("弹窗模板不存在: {}", name));
+        }
+    }
+
+    // 保存配置到文件
+    save_config(&state, &app).await.map_err(|e| format!("保存配置失败: {}", e))?;
+    Ok(())
+}
+
+/// 按弹窗请求的输入约束校验用户输入（与服务端校验规则一致）
+#[tauri::command]
+pub async fn validate_popup_input(
+    constraints: crate::mcp::types::InputConstraints,
+    input: String,
+) -> Result<(), String> {
+    crate::mcp::utils::validate_input_constraints(&constraints, input.trim())
+}
+
+/// 获取所有未过期的记住的回答
+#[tauri::command]
+pub async fn get_remembered_answers() -> Result<HashMap<String, crate::mcp::handlers::RememberedAnswer>, String> {
+    crate::mcp::handlers::load_remembered_answers().map_err(|e| format!("读取记住的回答失败: {}", e))
+}
+
+/// 删除指定键的记住的回答
+#[tauri::command]
+pub async fn delete_remembered_answer(key: String) -> Result<bool, String> {
+    crate::mcp::handlers::remove_remembered_answer(&key).map_err(|e| format!("删除记住的回答失败: {}", e))
+}
+
+/// 清空所有记住的回答
+#[tauri::command]
+pub async fn clear_remembered_answers() -> Result<usize, String> {
+    crate::mcp::handlers::remove_all_remembered_answers().map_err(|e| format!("清空记住的回答失败: {}", e))
+}
+
 // acemcp 相关命令已迁移
 
 // 已移除 Python Web 服务相关函数，完全使用 Rust 实现
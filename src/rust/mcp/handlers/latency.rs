@@ -0,0 +1,178 @@
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+
+use crate::config::get_standalone_config_path;
+use crate::constants::{app, mcp};
+use crate::mcp::handlers::record_stage_latency;
+use crate::mcp::types::PopupRequest;
+use crate::utils::{with_file_lock, write_file_atomic};
+use crate::{log_important, log_with_trace};
+
+/// 一次弹窗各阶段的耗时（毫秒），无法计算的阶段为 None
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PopupLatency {
+    pub dispatch_ms: Option<i64>, // 寸止收到调用 -> 启动等一下
+    pub ui_launch_ms: Option<i64>, // 启动等一下 -> 请求展示给用户
+    pub user_ms: Option<i64>, // 请求展示 -> 用户回答
+    pub delivery_ms: Option<i64>, // 用户回答 -> 寸止收到响应
+    pub total_ms: Option<i64>, // 寸止收到调用 -> 寸止收到响应
+}
+
+/// 各阶段耗时的分位数统计
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct StageLatencyStats {
+    pub count: usize,
+    pub p50_ms: Option<i64>,
+    pub p95_ms: Option<i64>,
+}
+
+/// 最近若干次弹窗的耗时统计
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct LatencyStats {
+    pub samples: usize,
+    pub dispatch: StageLatencyStats,
+    pub ui_launch: StageLatencyStats,
+    pub user: StageLatencyStats,
+    pub delivery: StageLatencyStats,
+    pub total: StageLatencyStats,
+}
+
+/// 各时间点均为 Unix 毫秒时间戳
+pub struct PopupTimestamps {
+    pub sent_at: Option<i64>,
+    pub spawned_at: i64,
+    pub ui_launched_at: Option<i64>,
+    pub answered_at: Option<i64>,
+    pub completed_at: i64,
+}
+
+impl PopupLatency {
+    /// 根据各时间点计算阶段耗时
+    pub fn from_timestamps(timestamps: &PopupTimestamps) -> Self {
+        let between = |from: Option<i64>, to: Option<i64>| match (from, to) {
+            (Some(from), Some(to)) => Some((to - from).max(0)),
+            _ => None,
+        };
+        let spawned_at = Some(timestamps.spawned_at);
+        let completed_at = Some(timestamps.completed_at);
+
+        Self {
+            dispatch_ms: between(timestamps.sent_at, spawned_at),
+            ui_launch_ms: between(spawned_at, timestamps.ui_launched_at),
+            user_ms: between(timestamps.ui_launched_at, timestamps.answered_at),
+            delivery_ms: between(timestamps.answered_at, completed_at),
+            total_ms: between(timestamps.sent_at, completed_at),
+        }
+    }
+
+    fn stages(&self) -> [(&'static str, Option<i64>); 5] {
+        [
+            ("dispatch", self.dispatch_ms),
+            ("ui_launch", self.ui_launch_ms),
+            ("user", self.user_ms),
+            ("delivery", self.delivery_ms),
+            ("total", self.total_ms),
+        ]
+    }
+}
+
+/// 记录一次弹窗的阶段耗时：输出汇总日志、更新指标并保存样本
+pub fn record_popup_latency(request: &PopupRequest, latency: &PopupLatency) {
+    let summary = latency
+        .stages()
+        .iter()
+        .map(|(stage, ms)| match ms {
+            Some(ms) => format!("{}={}ms", stage, ms),
+            None => format!("{}=-", stage),
+        })
+        .collect::<Vec<_>>()
+        .join(" ");
+    log_with_trace!(info, request.trace_label(), "弹窗耗时: id={} {}", request.id, summary);
+
+    for (stage, ms) in latency.stages() {
+        if let Some(ms) = ms {
+            record_stage_latency(stage, ms as u64);
+        }
+    }
+
+    if let Err(e) = append_latency_sample(latency) {
+        log_important!(warn, "保存耗时样本失败: {}", e);
+    }
+}
+
+/// 耗时样本文件路径，与配置文件同目录
+fn latency_samples_path() -> Result<PathBuf> {
+    Ok(get_standalone_config_path()?.with_file_name(app::LATENCY_SAMPLES_FILE_NAME))
+}
+
+fn load_latency_samples() -> Result<Vec<PopupLatency>> {
+    let path = latency_samples_path()?;
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    Ok(serde_json::from_str(&fs::read_to_string(&path)?)?)
+}
+
+/// 追加样本，只保留最近的 `LATENCY_SAMPLE_LIMIT` 条
+///
+/// 多个寸止进程可能同时追加，持有文件锁完成读取和写入
+fn append_latency_sample(latency: &PopupLatency) -> Result<()> {
+    let path = latency_samples_path()?;
+    with_file_lock(&path, || {
+        let mut samples = load_latency_samples()?;
+        samples.push(latency.clone());
+        if samples.len() > mcp::LATENCY_SAMPLE_LIMIT {
+            samples.drain(..samples.len() - mcp::LATENCY_SAMPLE_LIMIT);
+        }
+        write_file_atomic(&path, serde_json::to_string(&samples)?)?;
+        Ok(())
+    })
+}
+
+/// 最近邻秩法计算分位数
+fn percentile(sorted: &[i64], p: f64) -> Option<i64> {
+    if sorted.is_empty() {
+        return None;
+    }
+    let rank = ((p / 100.0) * sorted.len() as f64).ceil() as usize;
+    Some(sorted[rank.clamp(1, sorted.len()) - 1])
+}
+
+fn stage_stats(values: impl Iterator<Item = Option<i64>>) -> StageLatencyStats {
+    let mut values: Vec<i64> = values.flatten().collect();
+    values.sort_unstable();
+    StageLatencyStats {
+        count: values.len(),
+        p50_ms: percentile(&values, 50.0),
+        p95_ms: percentile(&values, 95.0),
+    }
+}
+
+/// 计算最近若干次弹窗各阶段的 p50/p95
+pub fn latency_stats() -> Result<LatencyStats> {
+    let samples = load_latency_samples()?;
+    Ok(LatencyStats {
+        samples: samples.len(),
+        dispatch: stage_stats(samples.iter().map(|s| s.dispatch_ms)),
+        ui_launch: stage_stats(samples.iter().map(|s| s.ui_launch_ms)),
+        user: stage_stats(samples.iter().map(|s| s.user_ms)),
+        delivery: stage_stats(samples.iter().map(|s| s.delivery_ms)),
+        total: stage_stats(samples.iter().map(|s| s.total_ms)),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_percentile_nearest_rank() {
+        let values: Vec<i64> = (1..=20).collect();
+
+        assert_eq!(percentile(&values, 50.0), Some(10));
+        assert_eq!(percentile(&values, 95.0), Some(19));
+        assert_eq!(percentile(&[], 50.0), None);
+    }
+}
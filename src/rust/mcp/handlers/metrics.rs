@@ -0,0 +1,157 @@
+use std::fmt::Write as _;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpListener;
+
+use crate::mcp::handlers::{pending_popup_count, PopupOutcome};
+use crate::{log_debug, log_important};
+
+/// 弹窗等待时长直方图的分桶上限（秒）
+const LATENCY_BUCKETS_SECS: [u64; 9] = [1, 5, 15, 30, 60, 120, 300, 600, 1800];
+
+static POPUPS_ANSWERED: AtomicU64 = AtomicU64::new(0);
+static POPUPS_CANCELLED: AtomicU64 = AtomicU64::new(0);
+static POPUPS_TIMED_OUT: AtomicU64 = AtomicU64::new(0);
+static POPUPS_FAILED: AtomicU64 = AtomicU64::new(0);
+
+static LATENCY_BUCKETS: [AtomicU64; LATENCY_BUCKETS_SECS.len()] = [const { AtomicU64::new(0) }; LATENCY_BUCKETS_SECS.len()];
+static LATENCY_COUNT: AtomicU64 = AtomicU64::new(0);
+static LATENCY_SUM_MILLIS: AtomicU64 = AtomicU64::new(0);
+
+/// 弹窗各阶段耗时的阶段名称
+const LATENCY_STAGES: [&str; 5] = ["dispatch", "ui_launch", "user", "delivery", "total"];
+
+static STAGE_COUNT: [AtomicU64; LATENCY_STAGES.len()] = [const { AtomicU64::new(0) }; LATENCY_STAGES.len()];
+static STAGE_SUM_MILLIS: [AtomicU64; LATENCY_STAGES.len()] = [const { AtomicU64::new(0) }; LATENCY_STAGES.len()];
+
+/// 记录一次弹窗结果（失败时 outcome 为 None）
+pub fn record_popup_result(outcome: Option<PopupOutcome>, elapsed: Duration) {
+    let counter = match outcome {
+        Some(PopupOutcome::Answered) => &POPUPS_ANSWERED,
+        Some(PopupOutcome::Cancelled) => &POPUPS_CANCELLED,
+        Some(PopupOutcome::Expired) => &POPUPS_TIMED_OUT,
+        None => &POPUPS_FAILED,
+    };
+    counter.fetch_add(1, Ordering::Relaxed);
+
+    // 直方图分桶为累计计数
+    let elapsed_secs = elapsed.as_secs_f64();
+    for (bucket, upper) in LATENCY_BUCKETS.iter().zip(LATENCY_BUCKETS_SECS) {
+        if elapsed_secs <= upper as f64 {
+            bucket.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+    LATENCY_COUNT.fetch_add(1, Ordering::Relaxed);
+    LATENCY_SUM_MILLIS.fetch_add(elapsed.as_millis() as u64, Ordering::Relaxed);
+}
+
+/// 记录弹窗某一阶段的耗时
+pub fn record_stage_latency(stage: &str, millis: u64) {
+    if let Some(index) = LATENCY_STAGES.iter().position(|name| *name == stage) {
+        STAGE_COUNT[index].fetch_add(1, Ordering::Relaxed);
+        STAGE_SUM_MILLIS[index].fetch_add(millis, Ordering::Relaxed);
+    }
+}
+
+/// 以 Prometheus 文本格式输出指标
+pub fn render_metrics() -> String {
+    let mut output = String::new();
+    let answered = POPUPS_ANSWERED.load(Ordering::Relaxed);
+    let cancelled = POPUPS_CANCELLED.load(Ordering::Relaxed);
+    let timed_out = POPUPS_TIMED_OUT.load(Ordering::Relaxed);
+    let failed = POPUPS_FAILED.load(Ordering::Relaxed);
+
+    let _ = writeln!(output, "# HELP cunzhi_popups_total 已完成的弹窗数量");
+    let _ = writeln!(output, "# TYPE cunzhi_popups_total counter");
+    let _ = writeln!(output, "cunzhi_popups_total{{result=\"answered\"}} {}", answered);
+    let _ = writeln!(output, "cunzhi_popups_total{{result=\"cancelled\"}} {}", cancelled);
+    let _ = writeln!(output, "cunzhi_popups_total{{result=\"timeout\"}} {}", timed_out);
+    let _ = writeln!(output, "cunzhi_popups_total{{result=\"error\"}} {}", failed);
+
+    let _ = writeln!(output, "# HELP cunzhi_popups_pending 正在等待用户响应的弹窗数量");
+    let _ = writeln!(output, "# TYPE cunzhi_popups_pending gauge");
+    let _ = writeln!(output, "cunzhi_popups_pending {}", pending_popup_count());
+
+    let _ = writeln!(output, "# HELP cunzhi_popup_duration_seconds 弹窗从发出到结束的耗时");
+    let _ = writeln!(output, "# TYPE cunzhi_popup_duration_seconds histogram");
+    for (bucket, upper) in LATENCY_BUCKETS.iter().zip(LATENCY_BUCKETS_SECS) {
+        let _ = writeln!(
+            output,
+            "cunzhi_popup_duration_seconds_bucket{{le=\"{}\"}} {}",
+            upper,
+            bucket.load(Ordering::Relaxed)
+        );
+    }
+    let count = LATENCY_COUNT.load(Ordering::Relaxed);
+    let _ = writeln!(output, "cunzhi_popup_duration_seconds_bucket{{le=\"+Inf\"}} {}", count);
+    let _ = writeln!(
+        output,
+        "cunzhi_popup_duration_seconds_sum {:.3}",
+        LATENCY_SUM_MILLIS.load(Ordering::Relaxed) as f64 / 1000.0
+    );
+    let _ = writeln!(output, "cunzhi_popup_duration_seconds_count {}", count);
+
+    let _ = writeln!(output, "# HELP cunzhi_popup_stage_seconds 弹窗各阶段耗时");
+    let _ = writeln!(output, "# TYPE cunzhi_popup_stage_seconds summary");
+    for (index, stage) in LATENCY_STAGES.iter().enumerate() {
+        let _ = writeln!(
+            output,
+            "cunzhi_popup_stage_seconds_sum{{stage=\"{}\"}} {:.3}",
+            stage,
+            STAGE_SUM_MILLIS[index].load(Ordering::Relaxed) as f64 / 1000.0
+        );
+        let _ = writeln!(
+            output,
+            "cunzhi_popup_stage_seconds_count{{stage=\"{}\"}} {}",
+            stage,
+            STAGE_COUNT[index].load(Ordering::Relaxed)
+        );
+    }
+
+    output
+}
+
+/// 启动指标 HTTP 监听（仅提供 GET /metrics）
+pub async fn start_metrics_listener(addr: String) {
+    let listener = match TcpListener::bind(&addr).await {
+        Ok(listener) => listener,
+        Err(e) => {
+            log_important!(warn, "启动指标监听失败 ({}): {}", addr, e);
+            return;
+        }
+    };
+    log_important!(info, "指标监听已启动: http://{}/metrics", addr);
+
+    loop {
+        let (mut stream, peer) = match listener.accept().await {
+            Ok(connection) => connection,
+            Err(e) => {
+                log_debug!("接受指标连接失败: {}", e);
+                continue;
+            }
+        };
+
+        tokio::spawn(async move {
+            let mut buffer = [0u8; 1024];
+            let read = stream.read(&mut buffer).await.unwrap_or(0);
+            let request = String::from_utf8_lossy(&buffer[..read]);
+
+            let response = if request.starts_with("GET /metrics ") {
+                let body = render_metrics();
+                format!(
+                    "HTTP/1.1 200 OK\r\nContent-Type: text/plain; version=0.0.4\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                    body.len(),
+                    body
+                )
+            } else {
+                "HTTP/1.1 404 Not Found\r\nContent-Length: 0\r\nConnection: close\r\n\r\n".to_string()
+            };
+
+            if let Err(e) = stream.write_all(response.as_bytes()).await {
+                log_debug!("发送指标响应失败 ({}): {}", peer, e);
+            }
+        });
+    }
+}
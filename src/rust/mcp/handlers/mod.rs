@@ -1,5 +1,17 @@
+pub mod latency;
+pub mod metrics;
 pub mod popup;
+pub mod quiet_hours;
+pub mod remember;
 pub mod response;
+pub mod template;
+pub mod webhook;
 
+pub use latency::*;
+pub use metrics::*;
 pub use popup::*;
+pub use quiet_hours::*;
+pub use remember::*;
 pub use response::*;
+pub use template::*;
+pub use webhook::*;
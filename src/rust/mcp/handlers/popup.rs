@@ -2,49 +2,340 @@ use anyhow::Result;
 use std::process::Command;
 use std::fs;
 use std::path::Path;
+use std::sync::atomic::{AtomicUsize, Ordering};
 
-use crate::mcp::types::PopupRequest;
+use crate::config::{config_path_override, load_standalone_config};
+use crate::constants::{app, mcp};
+use crate::i18n::{t, tr, Msg};
+use crate::mcp::handlers::{
+    current_quiet_hours_action, lookup_remembered_answer, record_popup_latency, remember_answer_if_requested,
+    PopupLatency, PopupTimestamps, QuietHoursAction,
+};
+use crate::mcp::types::{build_send_response, LaunchFailure, PopupError, PopupErrorCode, PopupRequest};
+use crate::mcp::utils::is_expired_at;
+use crate::utils::{create_pending_marker, request_file_path, PendingMarker};
+use crate::{log_debug, log_with_trace};
+
+/// 当前进程中正在等待用户响应的弹窗数量
+static PENDING_POPUPS: AtomicUsize = AtomicUsize::new(0);
+
+/// 待处理弹窗计数守卫，无论成功、失败还是取消都会在离开作用域时递减
+///
+/// 同时在运行时目录创建标记文件，等一下据此统计所有寸止进程的待处理弹窗
+struct PendingPopupGuard {
+    _marker: Option<PendingMarker>,
+}
+
+impl PendingPopupGuard {
+    fn new(request_id: &str) -> Self {
+        let count = PENDING_POPUPS.fetch_add(1, Ordering::SeqCst) + 1;
+        log_debug!("待处理弹窗数量: {}", count);
+
+        let marker = match create_pending_marker(request_id) {
+            Ok(marker) => Some(marker),
+            Err(e) => {
+                log_debug!("创建待处理弹窗标记失败: {}", e);
+                None
+            }
+        };
+        Self { _marker: marker }
+    }
+}
+
+impl Drop for PendingPopupGuard {
+    fn drop(&mut self) {
+        let count = PENDING_POPUPS.fetch_sub(1, Ordering::SeqCst) - 1;
+        log_debug!("待处理弹窗数量: {}", count);
+    }
+}
+
+/// 获取当前待处理弹窗数量
+pub fn pending_popup_count() -> usize {
+    PENDING_POPUPS.load(Ordering::SeqCst)
+}
+
+/// 检查请求是否已过期，过期时记录审计日志
+pub fn check_request_expired(request: &PopupRequest) -> bool {
+    let Some(expires_at) = request.expires_at else {
+        return false;
+    };
+
+    let skew_ms = load_standalone_config()
+        .ok()
+        .and_then(|config| config.mcp_config.expiry_clock_skew_ms)
+        .unwrap_or(mcp::DEFAULT_EXPIRY_CLOCK_SKEW_MS);
+    let now_ms = chrono::Utc::now().timestamp_millis();
+
+    if !is_expired_at(Some(expires_at), now_ms, skew_ms) {
+        return false;
+    }
+
+    log_with_trace!(
+        info,
+        request.trace_label(),
+        "请求已过期，不显示弹窗: id={}, now={} > expires_at={} + skew={} (超出 {}ms)",
+        request.id,
+        now_ms,
+        expires_at,
+        skew_ms,
+        now_ms - expires_at - skew_ms as i64
+    );
+    true
+}
+
+/// 按勿扰模式和免打扰时段配置处理请求，需要直接返回响应时返回 Some
+///
+/// 勿扰模式优先于免打扰时段。`defer` 会阻塞到勿扰关闭或免打扰结束
+/// （期间定期重新检查配置和请求是否过期），`telegram_only` 由等一下在选择交互渠道时处理
+fn apply_quiet_hours(request: &PopupRequest) -> Option<String> {
+    let mut deferred = false;
+
+    loop {
+        let config = load_standalone_config().ok()?;
+
+        let action = if config.dnd.enabled {
+            if config.dnd.behavior == mcp::DND_REJECT {
+                log_with_trace!(info, request.trace_label(), "勿扰模式自动回复: id={}", request.id);
+                return Some(build_send_response(
+                    Some(config.dnd.reject_text),
+                    vec![],
+                    vec![],
+                    Some(request.id.clone()),
+                    mcp::DND_AUTO_SOURCE,
+                ));
+            }
+            // 勿扰模式没有结束时间，按固定间隔重新检查
+            QuietHoursAction::Defer {
+                until: chrono::Local::now().naive_local()
+                    + chrono::Duration::seconds(mcp::QUIET_HOURS_RECHECK_SECS as i64),
+            }
+        } else {
+            current_quiet_hours_action(&config.quiet_hours)
+        };
+
+        match action {
+            QuietHoursAction::None | QuietHoursAction::TelegramOnly => {
+                if deferred {
+                    log_with_trace!(info, request.trace_label(), "勿扰/免打扰结束，继续弹窗: id={}", request.id);
+                }
+                return None;
+            }
+            QuietHoursAction::AutoRespond { text } => {
+                log_with_trace!(info, request.trace_label(), "免打扰时段自动回复: id={}", request.id);
+                return Some(build_send_response(
+                    Some(text),
+                    vec![],
+                    vec![],
+                    Some(request.id.clone()),
+                    mcp::QUIET_HOURS_AUTO_SOURCE,
+                ));
+            }
+            QuietHoursAction::Defer { until } => {
+                if !deferred {
+                    if config.dnd.enabled {
+                        log_with_trace!(info, request.trace_label(), "勿扰模式，暂缓弹窗: id={}", request.id);
+                    } else {
+                        log_with_trace!(info, request.trace_label(), "免打扰时段，暂缓弹窗至 {}: id={}", until, request.id);
+                    }
+                    deferred = true;
+                }
+
+                if check_request_expired(request) {
+                    return Some(mcp::EXPIRED_RESPONSE.to_string());
+                }
+
+                let remaining = (until - chrono::Local::now().naive_local())
+                    .to_std()
+                    .unwrap_or_default()
+                    .min(std::time::Duration::from_secs(mcp::QUIET_HOURS_RECHECK_SECS));
+                std::thread::sleep(remaining.max(std::time::Duration::from_secs(1)));
+            }
+        }
+    }
+}
 
 /// 创建 Tauri 弹窗
 ///
-/// 优先调用与 MCP 服务器同目录的 UI 命令，找不到时使用全局版本
+/// 优先调用与 MCP 服务器同目录的 UI 命令，找不到时使用全局版本。
+/// 会阻塞到用户响应或勿扰/免打扰结束，异步上下文中需通过 `spawn_blocking` 调用
 pub fn create_tauri_popup(request: &PopupRequest) -> Result<String> {
-    // 创建临时请求文件 - 跨平台适配
-    let temp_dir = std::env::temp_dir();
-    let temp_file = temp_dir.join(format!("mcp_request_{}.json", request.id));
-    let request_json = serde_json::to_string_pretty(request)?;
-    fs::write(&temp_file, request_json)?;
+    // 已过期的请求直接返回，不再启动 UI
+    if check_request_expired(request) {
+        return Ok(mcp::EXPIRED_RESPONSE.to_string());
+    }
+
+    // 用户已记住该问题的回答时直接返回（紧急请求除外）
+    if let Some(response) = lookup_remembered_answer(request) {
+        return Ok(response);
+    }
+
+    // 勿扰模式或免打扰时段可能暂缓或直接回复
+    if let Some(response) = apply_quiet_hours(request) {
+        return Ok(response);
+    }
+
+    log_with_trace!(info, request.trace_label(), "创建弹窗: id={}", request.id);
 
     // 尝试找到等一下命令的路径
-    let command_path = find_ui_command()?;
+    let command_path = find_ui_command().map_err(|e| PopupError::new(PopupErrorCode::LaunchFailed, e))?;
 
-    // 调用等一下命令
-    let output = Command::new(&command_path)
+    // 创建请求文件（写入运行时目录，不使用全局可读的系统临时目录）
+    let transport_error = |e: anyhow::Error| PopupError::new(PopupErrorCode::TransportError, e);
+    let temp_file = request_file_path(&request.id).map_err(transport_error)?;
+    let request_json = serde_json::to_string_pretty(request).map_err(|e| transport_error(e.into()))?;
+    fs::write(&temp_file, request_json).map_err(|e| transport_error(e.into()))?;
+
+    // 调用等一下命令，并传递配置文件路径覆盖
+    let mut command = Command::new(&command_path);
+    command
         .arg("--mcp-request")
         .arg(temp_file.to_string_lossy().to_string())
-        .output()?;
+        .arg("--response-format")
+        .arg("json");
+    if let Some(config_path) = config_path_override() {
+        command.env(app::CONFIG_PATH_ENV, config_path);
+    }
+    // 弹窗真正显示时才计入待处理数量，暂缓期间不计入
+    let _pending_guard = PendingPopupGuard::new(&request.id);
+    let spawned_at = chrono::Utc::now().timestamp_millis();
+    let started_at = std::time::Instant::now();
+    let output = command.output();
 
     // 清理临时文件
     let _ = fs::remove_file(&temp_file);
 
-    if output.status.success() {
-        let response = String::from_utf8_lossy(&output.stdout);
-        let response = response.trim();
-        if response.is_empty() {
-            Ok("用户取消了操作".to_string())
-        } else {
-            Ok(response.to_string())
+    let output = output.map_err(|e| {
+        PopupError::new(PopupErrorCode::LaunchFailed, tr(Msg::UiProcessFailed, &[&format!("{}: {}", command_path, e)]))
+    })?;
+
+    log_with_trace!(info, request.trace_label(), "弹窗进程结束: id={}, status={}", request.id, output.status);
+
+    match output.status.code() {
+        Some(mcp::MCP_REQUEST_EXIT_ANSWERED) => {
+            let stdout = String::from_utf8_lossy(&output.stdout);
+            let (response, ui_launched_at) = parse_response_envelope(stdout.trim())
+                .map_err(|e| PopupError::new(PopupErrorCode::TransportError, e))?;
+            let answered_at = serde_json::from_str::<serde_json::Value>(&response)
+                .ok()
+                .and_then(|value| value["metadata"]["answered_at"].as_i64());
+            let latency = PopupLatency::from_timestamps(&PopupTimestamps {
+                sent_at: request.sent_at,
+                spawned_at,
+                ui_launched_at,
+                answered_at,
+                completed_at: chrono::Utc::now().timestamp_millis(),
+            });
+            record_popup_latency(request, &latency);
+
+            remember_answer_if_requested(request, &response);
+            Ok(response)
+        }
+        Some(mcp::MCP_REQUEST_EXIT_CANCELLED) => Ok("用户取消了操作".to_string()),
+        Some(mcp::MCP_REQUEST_EXIT_TIMEOUT) => Ok(mcp::EXPIRED_RESPONSE.to_string()),
+        _ => {
+            let failure = LaunchFailure {
+                binary_path: command_path,
+                exit_code: output.status.code(),
+                signal: termination_name(&output.status),
+                duration_ms: started_at.elapsed().as_millis() as u64,
+                stderr_tail: tail_lines(&String::from_utf8_lossy(&output.stderr), mcp::LAUNCH_STDERR_TAIL_LINES),
+            };
+            log_with_trace!(
+                warn,
+                request.trace_label(),
+                "等一下实例失败: id={}, binary={}, exit_code={:?}, signal={:?}, duration={}ms",
+                request.id,
+                failure.binary_path,
+                failure.exit_code,
+                failure.signal,
+                failure.duration_ms
+            );
+            Err(failure.into())
+        }
+    }
+}
+
+/// 保留文本的最后若干行
+fn tail_lines(text: &str, count: usize) -> String {
+    let lines: Vec<&str> = text.trim_end().lines().collect();
+    lines[lines.len().saturating_sub(count)..].join("\n")
+}
+
+/// 进程被信号终止时返回信号名（Unix）
+#[cfg(unix)]
+fn termination_name(status: &std::process::ExitStatus) -> Option<String> {
+    use std::os::unix::process::ExitStatusExt;
+
+    let signal = status.signal()?;
+    let name = match signal {
+        1 => "SIGHUP",
+        2 => "SIGINT",
+        3 => "SIGQUIT",
+        4 => "SIGILL",
+        6 => "SIGABRT",
+        7 => "SIGBUS",
+        8 => "SIGFPE",
+        9 => "SIGKILL",
+        11 => "SIGSEGV",
+        13 => "SIGPIPE",
+        15 => "SIGTERM",
+        _ => return Some(format!("signal {}", signal)),
+    };
+    Some(name.to_string())
+}
+
+/// 退出码为常见 NTSTATUS 时返回其名称（Windows）
+#[cfg(windows)]
+fn termination_name(status: &std::process::ExitStatus) -> Option<String> {
+    let name = match status.code()? as u32 {
+        0xC0000005 => "STATUS_ACCESS_VIOLATION",
+        0xC000001D => "STATUS_ILLEGAL_INSTRUCTION",
+        0xC00000FD => "STATUS_STACK_OVERFLOW",
+        0xC000013A => "STATUS_CONTROL_C_EXIT",
+        0xC0000135 => "STATUS_DLL_NOT_FOUND",
+        0xC0000139 => "STATUS_ENTRYPOINT_NOT_FOUND",
+        0xC0000142 => "STATUS_DLL_INIT_FAILED",
+        0xC0000409 => "STATUS_STACK_BUFFER_OVERRUN",
+        _ => return None,
+    };
+    Some(name.to_string())
+}
+
+/// 解析等一下输出的结果信封，同时返回请求展示给用户的时间
+///
+/// 旧版本等一下直接输出响应内容，此时按原样返回（空输出视为取消）
+fn parse_response_envelope(stdout: &str) -> Result<(String, Option<i64>)> {
+    if stdout.is_empty() {
+        return Ok(("用户取消了操作".to_string(), None));
+    }
+
+    let envelope = match serde_json::from_str::<serde_json::Value>(stdout) {
+        Ok(value) if value.get("status").is_some() => value,
+        _ => return Ok((stdout.to_string(), None)),
+    };
+    let ui_launched_at = envelope["ui_launched_at"].as_i64();
+
+    match envelope["status"].as_str() {
+        Some("answered") => Ok((
+            match &envelope["response"] {
+                serde_json::Value::String(response) => response.clone(),
+                response => response.to_string(),
+            },
+            ui_launched_at,
+        )),
+        Some("cancelled") => Ok(("用户取消了操作".to_string(), ui_launched_at)),
+        Some("timeout") => Ok((mcp::EXPIRED_RESPONSE.to_string(), ui_launched_at)),
+        _ => {
+            let error = envelope["error"].as_str().map(str::to_string).unwrap_or_else(|| t(Msg::UnknownError));
+            anyhow::bail!(tr(Msg::UiProcessFailed, &[&error]))
         }
-    } else {
-        let error = String::from_utf8_lossy(&output.stderr);
-        anyhow::bail!("UI进程失败: {}", error);
     }
 }
 
 /// 查找等一下 UI 命令的路径
 ///
 /// 按优先级查找：同目录 -> 全局版本 -> 开发环境
-fn find_ui_command() -> Result<String> {
+pub(crate) fn find_ui_command() -> Result<String> {
     // 1. 优先尝试与当前 MCP 服务器同目录的等一下命令
     if let Ok(current_exe) = std::env::current_exe() {
         if let Some(exe_dir) = current_exe.parent() {
@@ -61,16 +352,11 @@ fn find_ui_command() -> Result<String> {
     }
 
     // 3. 如果都找不到，返回详细错误信息
-    anyhow::bail!(
-        "找不到等一下 UI 命令。请确保：\n\
-         1. 已编译项目：cargo build --release\n\
-         2. 或已全局安装：./install.sh\n\
-         3. 或等一下命令在同目录下"
-    )
+    anyhow::bail!(t(Msg::UiCommandNotFound))
 }
 
 /// 测试命令是否可用
-fn test_command_available(command: &str) -> bool {
+pub(crate) fn test_command_available(command: &str) -> bool {
     Command::new(command)
         .arg("--version")
         .output()
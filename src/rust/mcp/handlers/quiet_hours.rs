@@ -0,0 +1,155 @@
+use chrono::{Datelike, Duration, Local, NaiveDateTime, NaiveTime, TimeZone};
+
+use crate::config::QuietHoursConfig;
+use crate::constants::mcp;
+
+/// 免打扰时段下的弹窗处理方式
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum QuietHoursAction {
+    /// 不在免打扰时段，正常弹窗
+    None,
+    /// 暂缓弹窗直到免打扰结束
+    Defer { until: NaiveDateTime },
+    /// 跳过界面，仅通过 Telegram 交互
+    TelegramOnly,
+    /// 使用预设文本自动回复
+    AutoRespond { text: String },
+}
+
+/// 免打扰状态（供前端展示）
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct QuietHoursState {
+    pub enabled: bool,
+    pub active: bool,            // 当前是否处于免打扰时段（已考虑临时忽略）
+    pub overridden: bool,        // 是否已临时忽略免打扰
+    pub behavior: String,
+    pub ends_at: Option<i64>,    // 当前免打扰时段结束时间（Unix毫秒时间戳）
+}
+
+/// 解析 "HH:MM" 格式的时间
+fn parse_time(value: &str) -> Option<NaiveTime> {
+    NaiveTime::parse_from_str(value.trim(), "%H:%M").ok()
+}
+
+/// 本地时间转换为 Unix 毫秒时间戳
+fn to_timestamp_millis(time: NaiveDateTime) -> Option<i64> {
+    Local
+        .from_local_datetime(&time)
+        .earliest()
+        .map(|time| time.timestamp_millis())
+}
+
+/// 计算从指定日期开始的免打扰时段，该日期不生效时返回 None
+fn window_starting_on(config: &QuietHoursConfig, date: chrono::NaiveDate) -> Option<(NaiveDateTime, NaiveDateTime)> {
+    if !config.days.contains(&date.weekday().number_from_monday()) {
+        return None;
+    }
+
+    let start = parse_time(&config.start)?;
+    let end = parse_time(&config.end)?;
+    let start_at = date.and_time(start);
+    let end_at = if end > start {
+        date.and_time(end)
+    } else {
+        // 结束时间不晚于开始时间，表示跨夜
+        (date + Duration::days(1)).and_time(end)
+    };
+
+    Some((start_at, end_at))
+}
+
+/// 当前所在免打扰时段的结束时间（不考虑临时忽略）
+pub fn active_quiet_window_end(config: &QuietHoursConfig, now: NaiveDateTime) -> Option<NaiveDateTime> {
+    if !config.enabled {
+        return None;
+    }
+
+    // 跨夜时段可能从前一天开始
+    [now.date() - Duration::days(1), now.date()]
+        .into_iter()
+        .filter_map(|date| window_starting_on(config, date))
+        .find(|(start_at, end_at)| *start_at <= now && now < *end_at)
+        .map(|(_, end_at)| end_at)
+}
+
+/// 当前或下一个免打扰时段的结束时间
+pub fn next_quiet_window_end(config: &QuietHoursConfig, now: NaiveDateTime) -> Option<NaiveDateTime> {
+    if let Some(end_at) = active_quiet_window_end(config, now) {
+        return Some(end_at);
+    }
+
+    (0..8)
+        .filter_map(|offset| window_starting_on(config, now.date() + Duration::days(offset)))
+        .find(|(start_at, _)| *start_at > now)
+        .map(|(_, end_at)| end_at)
+}
+
+/// 是否已临时忽略免打扰
+fn is_overridden(config: &QuietHoursConfig, now: NaiveDateTime) -> bool {
+    match (config.override_until, to_timestamp_millis(now)) {
+        (Some(until), Some(now_ms)) => now_ms < until,
+        _ => false,
+    }
+}
+
+/// 根据配置决定当前弹窗请求的处理方式
+pub fn quiet_hours_action(config: &QuietHoursConfig, now: NaiveDateTime) -> QuietHoursAction {
+    let Some(end_at) = active_quiet_window_end(config, now) else {
+        return QuietHoursAction::None;
+    };
+
+    if is_overridden(config, now) {
+        return QuietHoursAction::None;
+    }
+
+    match config.behavior.as_str() {
+        mcp::QUIET_HOURS_TELEGRAM_ONLY => QuietHoursAction::TelegramOnly,
+        mcp::QUIET_HOURS_AUTO_RESPOND => QuietHoursAction::AutoRespond {
+            text: config.auto_response_text.clone(),
+        },
+        _ => QuietHoursAction::Defer { until: end_at },
+    }
+}
+
+/// 使用当前本地时间决定弹窗处理方式
+pub fn current_quiet_hours_action(config: &QuietHoursConfig) -> QuietHoursAction {
+    quiet_hours_action(config, Local::now().naive_local())
+}
+
+/// 获取当前免打扰状态
+pub fn quiet_hours_state(config: &QuietHoursConfig) -> QuietHoursState {
+    let now = Local::now().naive_local();
+    let window_end = active_quiet_window_end(config, now);
+    let overridden = is_overridden(config, now);
+
+    QuietHoursState {
+        enabled: config.enabled,
+        active: window_end.is_some() && !overridden,
+        overridden,
+        behavior: config.behavior.clone(),
+        ends_at: window_end.and_then(to_timestamp_millis),
+    }
+}
+
+/// 计算“忽略今晚的免打扰”的截止时间（当前或下一个时段结束时）
+pub fn quiet_hours_override_until(config: &QuietHoursConfig) -> Option<i64> {
+    next_quiet_window_end(config, Local::now().naive_local()).and_then(to_timestamp_millis)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::default_quiet_hours_config;
+
+    #[test]
+    fn test_overnight_quiet_window() {
+        let mut config = default_quiet_hours_config();
+        config.enabled = true;
+
+        let at = |value: &str| NaiveDateTime::parse_from_str(value, "%Y-%m-%d %H:%M").unwrap();
+
+        assert_eq!(active_quiet_window_end(&config, at("2025-01-06 23:30")), Some(at("2025-01-07 07:00")));
+        assert_eq!(active_quiet_window_end(&config, at("2025-01-07 06:59")), Some(at("2025-01-07 07:00")));
+        assert_eq!(active_quiet_window_end(&config, at("2025-01-07 12:00")), None);
+    }
+}
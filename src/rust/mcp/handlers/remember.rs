@@ -0,0 +1,179 @@
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+
+use crate::config::{get_standalone_config_path, load_standalone_config};
+use crate::constants::{app, mcp};
+use crate::mcp::handlers::check_response_constraints;
+use crate::mcp::types::PopupRequest;
+use crate::utils::{with_file_lock, write_file_atomic};
+use crate::{log_debug, log_important, log_with_trace};
+
+/// 记住的回答
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct RememberedAnswer {
+    pub response: String, // 等一下返回的结构化响应
+    pub saved_at: i64, // 保存时间（Unix毫秒时间戳）
+    pub ttl_secs: u64, // 有效期(秒)
+}
+
+impl RememberedAnswer {
+    /// 是否已超过有效期
+    pub fn is_expired(&self, now_ms: i64) -> bool {
+        now_ms - self.saved_at > (self.ttl_secs as i64).saturating_mul(1000)
+    }
+}
+
+/// 记住的回答文件路径，与配置文件同目录
+fn remembered_answers_path() -> Result<PathBuf> {
+    Ok(get_standalone_config_path()?.with_file_name(app::REMEMBERED_ANSWERS_FILE_NAME))
+}
+
+/// 读取所有记住的回答（包含已过期的）
+fn load_all_remembered_answers() -> Result<HashMap<String, RememberedAnswer>> {
+    let path = remembered_answers_path()?;
+    if !path.exists() {
+        return Ok(HashMap::new());
+    }
+
+    let json = fs::read_to_string(&path)?;
+    Ok(serde_json::from_str(&json)?)
+}
+
+/// 保存记住的回答
+fn save_remembered_answers(answers: &HashMap<String, RememberedAnswer>) -> Result<()> {
+    write_file_atomic(&remembered_answers_path()?, serde_json::to_string_pretty(answers)?)?;
+    Ok(())
+}
+
+/// 持有文件锁读取、修改并保存未过期的记住的回答
+///
+/// 多个寸止进程同时更新时不会丢失彼此的修改
+fn update_remembered_answers<T>(update: impl FnOnce(&mut HashMap<String, RememberedAnswer>) -> T) -> Result<T> {
+    with_file_lock(&remembered_answers_path()?, || {
+        let mut answers = load_remembered_answers()?;
+        let result = update(&mut answers);
+        save_remembered_answers(&answers)?;
+        Ok(result)
+    })
+}
+
+/// 获取所有未过期的记住的回答
+pub fn load_remembered_answers() -> Result<HashMap<String, RememberedAnswer>> {
+    let now_ms = chrono::Utc::now().timestamp_millis();
+    let mut answers = load_all_remembered_answers()?;
+    answers.retain(|_, answer| !answer.is_expired(now_ms));
+    Ok(answers)
+}
+
+/// 查找请求对应的记住的回答，找到时返回标记为 `from_memory` 的响应
+///
+/// 紧急请求始终显示弹窗，不使用记住的回答
+pub fn lookup_remembered_answer(request: &PopupRequest) -> Option<String> {
+    if request.urgent {
+        return None;
+    }
+    let key = request.remember_key.as_deref()?;
+
+    let answers = match load_remembered_answers() {
+        Ok(answers) => answers,
+        Err(e) => {
+            log_important!(warn, "读取记住的回答失败: {}", e);
+            return None;
+        }
+    };
+    let answer = answers.get(key)?;
+
+    let mut response: serde_json::Value = serde_json::from_str(&answer.response).ok()?;
+    let metadata = response.get_mut("metadata")?.as_object_mut()?;
+    metadata.insert("request_id".to_string(), serde_json::json!(request.id));
+    metadata.insert("timestamp".to_string(), serde_json::json!(chrono::Utc::now().to_rfc3339()));
+    metadata.insert("remember".to_string(), serde_json::json!(false));
+    metadata.insert("from_memory".to_string(), serde_json::json!(true));
+
+    log_with_trace!(info, request.trace_label(), "使用记住的回答，不显示弹窗: id={}, key={}", request.id, key);
+    Some(response.to_string())
+}
+
+/// 用户勾选“记住本次选择”时保存回答
+pub fn remember_answer_if_requested(request: &PopupRequest, response: &str) {
+    if request.urgent {
+        return;
+    }
+    let Some(key) = request.remember_key.as_deref() else {
+        return;
+    };
+
+    let remember = serde_json::from_str::<serde_json::Value>(response)
+        .ok()
+        .and_then(|value| value["metadata"]["remember"].as_bool())
+        .unwrap_or(false);
+    if !remember {
+        return;
+    }
+
+    // 不满足输入约束的回答不记住
+    if check_response_constraints(request, response).is_err() {
+        return;
+    }
+
+    let ttl_secs = load_standalone_config()
+        .ok()
+        .and_then(|config| config.mcp_config.remember_ttl_secs)
+        .unwrap_or(mcp::DEFAULT_REMEMBER_TTL_SECS);
+
+    let result = update_remembered_answers(|answers| {
+        answers.insert(
+            key.to_string(),
+            RememberedAnswer {
+                response: response.to_string(),
+                saved_at: chrono::Utc::now().timestamp_millis(),
+                ttl_secs,
+            },
+        );
+    });
+
+    match result {
+        Ok(()) => log_with_trace!(info, request.trace_label(), "已记住回答: key={}, ttl={}s", key, ttl_secs),
+        Err(e) => log_important!(warn, "保存记住的回答失败: key={}, error={}", key, e),
+    }
+}
+
+/// 删除指定键的记住的回答，返回是否存在
+pub fn remove_remembered_answer(key: &str) -> Result<bool> {
+    let removed = update_remembered_answers(|answers| answers.remove(key).is_some())?;
+    if removed {
+        log_debug!("已删除记住的回答: key={}", key);
+    }
+    Ok(removed)
+}
+
+/// 清空所有记住的回答，返回清除的数量
+pub fn remove_all_remembered_answers() -> Result<usize> {
+    let count = update_remembered_answers(|answers| {
+        let count = answers.len();
+        answers.clear();
+        count
+    })?;
+    log_important!(info, "已清空记住的回答: {} 条", count);
+    Ok(count)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_remembered_answer_expiry() {
+        let answer = RememberedAnswer {
+            response: "{}".to_string(),
+            saved_at: 1_000,
+            ttl_secs: 60,
+        };
+
+        assert!(!answer.is_expired(61_000));
+        assert!(answer.is_expired(61_001));
+    }
+}
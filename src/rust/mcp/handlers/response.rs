@@ -1,14 +1,94 @@
 use anyhow::Result;
-use rmcp::{Error as McpError, model::Content};
+use rmcp::{Error as McpError, model::{Content, ResourceContents}};
 
-use crate::mcp::types::{McpResponse, McpResponseContent};
+use crate::constants::mcp;
+use crate::mcp::types::{McpResponse, McpResponseContent, PopupRequest};
+use crate::mcp::utils::{check_response_attachments, decode_attachment, validate_input_constraints};
+
+/// 弹窗响应的结果类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PopupOutcome {
+    Answered,
+    Cancelled,
+    Expired,
+}
+
+impl PopupOutcome {
+    /// 结果状态名称（用于 JSON 输出和 webhook）
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PopupOutcome::Answered => "answered",
+            PopupOutcome::Cancelled => "cancelled",
+            PopupOutcome::Expired => "timeout",
+        }
+    }
+
+    /// `--mcp-request` 模式下对应的进程退出码
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            PopupOutcome::Answered => mcp::MCP_REQUEST_EXIT_ANSWERED,
+            PopupOutcome::Cancelled => mcp::MCP_REQUEST_EXIT_CANCELLED,
+            PopupOutcome::Expired => mcp::MCP_REQUEST_EXIT_TIMEOUT,
+        }
+    }
+}
+
+/// 判断弹窗响应的结果类型（兼容带引号的 JSON 字符串形式）
+pub fn classify_popup_response(response: &str) -> PopupOutcome {
+    match response.trim().trim_matches('"') {
+        "" | "CANCELLED" | "用户取消了操作" => PopupOutcome::Cancelled,
+        r if r == mcp::EXPIRED_RESPONSE => PopupOutcome::Expired,
+        _ => PopupOutcome::Answered,
+    }
+}
+
+/// 服务端按请求的输入约束重新校验用户回答，避免绕过等一下的校验
+///
+/// 只校验用户直接输入的回答（继续、增强等操作生成的内容不受约束），
+/// 已选择预定义选项且未填写文本时视为满足输入约束；
+/// 选择了需确认的选项时回答必须带有 `confirmed` 标记
+pub fn check_response_constraints(request: &PopupRequest, response: &str) -> std::result::Result<(), String> {
+    if request.input_constraints.is_none() && request.confirm_options.is_empty() {
+        return Ok(());
+    }
+    let Ok(response) = serde_json::from_str::<McpResponse>(response) else {
+        return Ok(());
+    };
+    if !matches!(response.metadata.source.as_deref(), Some("popup") | Some("telegram")) {
+        return Ok(());
+    }
+
+    if !response.metadata.confirmed {
+        let unconfirmed: Vec<&str> = response
+            .selected_options
+            .iter()
+            .filter(|option| request.confirm_options.contains(option))
+            .map(String::as_str)
+            .collect();
+        if !unconfirmed.is_empty() {
+            return Err(format!("选项未经确认: {}", unconfirmed.join(", ")));
+        }
+    }
+
+    let Some(constraints) = &request.input_constraints else {
+        return Ok(());
+    };
+
+    let input = response.user_input.as_deref().unwrap_or("").trim();
+    if input.is_empty() && !response.selected_options.is_empty() {
+        return Ok(());
+    }
+    validate_input_constraints(constraints, input)
+}
 
 /// 解析 MCP 响应内容
 ///
 /// 支持新的结构化格式和旧格式的兼容性，并生成适当的 Content 对象
 pub fn parse_mcp_response(response: &str) -> Result<Vec<Content>, McpError> {
-    if response.trim() == "CANCELLED" || response.trim() == "用户取消了操作" {
-        return Ok(vec![Content::text("用户取消了操作".to_string())]);
+    match classify_popup_response(response) {
+        PopupOutcome::Cancelled => return Ok(vec![Content::text("用户取消了操作".to_string())]),
+        PopupOutcome::Expired => return Ok(vec![Content::text("请求已过期，用户未看到该消息".to_string())]),
+        PopupOutcome::Answered => {}
     }
 
     // 首先尝试解析为新的结构化格式
@@ -168,11 +248,56 @@ fn parse_structured_response(response: McpResponse) -> Result<Vec<Content>, McpE
         image_info_parts.push(image_info);
     }
 
-    // 4. 合并所有文本内容
+    // 4. 处理用户随回答发送的文件，作为资源内容返回
+    let mut attachment_info_parts = Vec::new();
+    if let Err(e) = check_response_attachments(&response.attachments) {
+        attachment_info_parts.push(format!("⚠️ 用户发送的附件已忽略: {}", e));
+    } else {
+        for (index, attachment) in response.attachments.iter().enumerate() {
+            let name = attachment.name.clone().unwrap_or_else(|| format!("attachment_{}", index + 1));
+            let uri = format!("attachment://{}/{}", index + 1, name);
+            let text = if attachment.mime.starts_with("text/") {
+                decode_attachment(attachment).ok().and_then(|data| String::from_utf8(data).ok())
+            } else {
+                None
+            };
+
+            result.push(Content::resource(match text {
+                Some(text) => ResourceContents::TextResourceContents {
+                    uri: uri.clone(),
+                    mime_type: Some(attachment.mime.clone()),
+                    text,
+                },
+                None => ResourceContents::BlobResourceContents {
+                    uri: uri.clone(),
+                    mime_type: Some(attachment.mime.clone()),
+                    blob: attachment.data_base64.clone(),
+                },
+            }));
+            attachment_info_parts.push(format!(
+                "=== 附件 {} ===\n文件名: {}\n类型: {}\n资源: {}",
+                index + 1, name, attachment.mime, uri
+            ));
+        }
+    }
+
+    // 5. 合并所有文本内容
     let mut all_text_parts = text_parts;
     all_text_parts.extend(image_info_parts);
+    all_text_parts.extend(attachment_info_parts);
+
+    if matches!(
+        response.metadata.source.as_deref(),
+        Some(mcp::DND_AUTO_SOURCE) | Some(mcp::QUIET_HOURS_AUTO_SOURCE)
+    ) {
+        all_text_parts.push("💡 注意：这是自动生成的回复，用户当前不在（勿扰/免打扰），未看到该消息。".to_string());
+    }
+
+    if response.metadata.from_memory {
+        all_text_parts.push("💡 注意：这是用户之前选择记住的回答，本次未显示弹窗。".to_string());
+    }
 
-    // 5. 添加兼容性说明
+    // 6. 添加兼容性说明
     if !response.images.is_empty() {
         all_text_parts.push(format!(
             "💡 注意：用户提供了 {} 张图片。如果 AI 助手无法显示图片，图片数据已包含在上述 Base64 信息中。",
@@ -180,16 +305,37 @@ fn parse_structured_response(response: McpResponse) -> Result<Vec<Content>, McpE
         ));
     }
 
-    // 6. 将文本内容添加到结果中（图片后面）
+    // 7. 将文本内容添加到结果中（图片后面）
     if !all_text_parts.is_empty() {
         let combined_text = all_text_parts.join("\n\n");
         result.push(Content::text(combined_text));
     }
 
-    // 7. 如果没有任何内容，添加默认响应
+    // 8. 如果没有任何内容，添加默认响应
     if result.is_empty() {
         result.push(Content::text("用户未提供任何内容".to_string()));
     }
 
     Ok(result)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mcp::types::build_mcp_response;
+
+    #[test]
+    fn test_confirm_options_require_confirmed_flag() {
+        let json = r#"{"id":"1","message":"测试","predefined_options":["保留","删除分支"],"is_markdown":true,"confirm_options":["删除分支"]}"#;
+        let request: PopupRequest = serde_json::from_str(json).unwrap();
+        let response = |options: Vec<&str>, confirmed: bool| {
+            let mut response = build_mcp_response(None, options.into_iter().map(String::from).collect(), vec![], None, "popup");
+            response["metadata"]["confirmed"] = serde_json::json!(confirmed);
+            response.to_string()
+        };
+
+        assert!(check_response_constraints(&request, &response(vec!["保留"], false)).is_ok());
+        assert!(check_response_constraints(&request, &response(vec!["删除分支"], false)).is_err());
+        assert!(check_response_constraints(&request, &response(vec!["删除分支"], true)).is_ok());
+    }
+}
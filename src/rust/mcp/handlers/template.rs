@@ -0,0 +1,64 @@
+use crate::config::{load_standalone_config, PopupTemplate};
+use crate::log_debug;
+use crate::mcp::types::ZhiRequest;
+
+/// 展开请求引用的弹窗模板
+///
+/// 在寸止侧展开，交给等一下或其他渠道的请求已包含完整内容
+pub fn expand_popup_template(request: &mut ZhiRequest) -> Result<(), String> {
+    let Some(name) = request.template.clone() else {
+        return Ok(());
+    };
+
+    let config = load_standalone_config().map_err(|e| format!("读取配置失败: {}", e))?;
+    let template = config
+        .popup_templates
+        .get(&name)
+        .ok_or_else(|| format!("弹窗模板不存在: {}", name))?;
+
+    apply_popup_template(request, template, chrono::Utc::now().timestamp_millis());
+    log_debug!("已展开弹窗模板: {}", name);
+    Ok(())
+}
+
+/// 用模板补全请求中未提供的字段（请求中提供的字段优先）
+pub fn apply_popup_template(request: &mut ZhiRequest, template: &PopupTemplate, now_ms: i64) {
+    if request.message.trim().is_empty() {
+        request.message = template.message.clone();
+    }
+    if request.predefined_options.is_empty() {
+        request.predefined_options = template.predefined_options.clone();
+    }
+    if request.allow_multiple.is_none() {
+        request.allow_multiple = Some(template.allow_multiple);
+    }
+    if request.expires_at.is_none() {
+        request.expires_at = template
+            .timeout_secs
+            .map(|secs| now_ms.saturating_add((secs as i64).saturating_mul(1000)));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_request_fields_override_template() {
+        let template = PopupTemplate {
+            message: "提交前是否运行测试？".to_string(),
+            predefined_options: vec!["运行".to_string(), "跳过".to_string()],
+            allow_multiple: false,
+            timeout_secs: Some(60),
+        };
+        let mut request: ZhiRequest =
+            serde_json::from_str(r#"{"template":"review","predefined_options":["仅运行单元测试"]}"#).unwrap();
+
+        apply_popup_template(&mut request, &template, 1_000);
+
+        assert_eq!(request.message, "提交前是否运行测试？");
+        assert_eq!(request.predefined_options, vec!["仅运行单元测试".to_string()]);
+        assert_eq!(request.allow_multiple, Some(false));
+        assert_eq!(request.expires_at, Some(61_000));
+    }
+}
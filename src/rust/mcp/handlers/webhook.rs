@@ -0,0 +1,211 @@
+use anyhow::Result;
+use reqwest::header::CONTENT_TYPE;
+use reqwest::Client;
+use ring::hmac;
+use std::time::Duration;
+
+use crate::config::{load_standalone_config, WebhookConfig};
+use crate::constants::network;
+use crate::mcp::handlers::{classify_popup_response, PopupOutcome};
+use crate::mcp::types::PopupRequest;
+use crate::mcp::utils::McpToolError;
+use crate::{log_debug, log_important};
+
+/// 签名请求头
+pub const SIGNATURE_HEADER: &str = "X-Cunzhi-Signature";
+
+/// 弹窗已回答事件
+pub const EVENT_POPUP_ANSWERED: &str = "popup_answered";
+
+/// 弹窗已取消事件
+pub const EVENT_POPUP_CANCELLED: &str = "popup_cancelled";
+
+/// 请求已过期事件
+pub const EVENT_POPUP_EXPIRED: &str = "popup_expired";
+
+/// 弹窗失败事件（启动失败、传输错误、响应无效等）
+pub const EVENT_POPUP_FAILED: &str = "popup_failed";
+
+/// 使用密钥计算请求体的 HMAC-SHA256 签名（十六进制）
+pub fn sign_payload(secret: &str, body: &str) -> String {
+    let key = hmac::Key::new(hmac::HMAC_SHA256, secret.as_bytes());
+    let tag = hmac::sign(&key, body.as_bytes());
+    hex::encode(tag.as_ref())
+}
+
+/// 判断 webhook 是否订阅了指定事件（未配置事件时订阅全部）
+fn is_subscribed(webhook: &WebhookConfig, event: &str) -> bool {
+    webhook.events.is_empty() || webhook.events.iter().any(|e| e == event)
+}
+
+/// 构建弹窗完成事件的请求体
+pub fn build_popup_event(request: &PopupRequest, response: &str, duration: Duration) -> serde_json::Value {
+    let event = match classify_popup_response(response) {
+        PopupOutcome::Answered => EVENT_POPUP_ANSWERED,
+        PopupOutcome::Cancelled => EVENT_POPUP_CANCELLED,
+        PopupOutcome::Expired => EVENT_POPUP_EXPIRED,
+    };
+    let parsed: Option<serde_json::Value> = serde_json::from_str(response).ok();
+
+    let channel = parsed
+        .as_ref()
+        .and_then(|v| v.get("metadata"))
+        .and_then(|m| m.get("source"))
+        .and_then(|s| s.as_str())
+        .unwrap_or("popup")
+        .to_string();
+
+    serde_json::json!({
+        "event": event,
+        "request_id": request.id,
+        "trace_id": request.trace_label(),
+        "response": parsed.unwrap_or_else(|| serde_json::Value::String(response.to_string())),
+        "channel": channel,
+        "duration_ms": duration.as_millis() as u64,
+        "metadata": {
+            "timestamp": chrono::Utc::now().to_rfc3339(),
+            "is_markdown": request.is_markdown,
+            "predefined_options": request.predefined_options,
+        }
+    })
+}
+
+/// 构建弹窗失败事件的请求体
+pub fn build_popup_failure_event(request: &PopupRequest, error: &McpToolError, duration: Duration) -> serde_json::Value {
+    serde_json::json!({
+        "event": EVENT_POPUP_FAILED,
+        "request_id": request.id,
+        "trace_id": request.trace_label(),
+        "error": {
+            "code": error.popup_code().map(|code| code.as_str()),
+            "message": error.to_string(),
+        },
+        "channel": "popup",
+        "duration_ms": duration.as_millis() as u64,
+        "metadata": {
+            "timestamp": chrono::Utc::now().to_rfc3339(),
+            "is_markdown": request.is_markdown,
+            "predefined_options": request.predefined_options,
+        }
+    })
+}
+
+/// 发送单次 webhook 请求
+async fn send_webhook(client: &Client, webhook: &WebhookConfig, body: &str) -> Result<()> {
+    let mut request = client
+        .post(&webhook.url)
+        .header(CONTENT_TYPE, "application/json")
+        .body(body.to_string());
+
+    if !webhook.secret.is_empty() {
+        request = request.header(SIGNATURE_HEADER, sign_payload(&webhook.secret, body));
+    }
+
+    let response = request.send().await?;
+    if !response.status().is_success() {
+        anyhow::bail!("webhook 返回状态码 {}", response.status());
+    }
+    Ok(())
+}
+
+/// 发送 webhook 请求，失败时按指数退避重试
+async fn deliver_with_retry(client: &Client, webhook: &WebhookConfig, body: &str) {
+    let mut retries = 0;
+    loop {
+        match send_webhook(client, webhook, body).await {
+            Ok(()) => {
+                log_debug!("webhook 已送达: {}", webhook.url);
+                return;
+            }
+            Err(e) => {
+                if retries >= network::WEBHOOK_RETRY_COUNT {
+                    log_important!(warn, "webhook 发送失败，已放弃 ({}): {}", webhook.url, e);
+                    return;
+                }
+                retries += 1;
+
+                let delay = network::WEBHOOK_RETRY_BASE_DELAY_MS * 2u64.pow(retries - 1);
+                log_debug!("webhook 发送失败，{}ms 后重试({}/{}): {}", delay, retries, network::WEBHOOK_RETRY_COUNT, e);
+                tokio::time::sleep(Duration::from_millis(delay)).await;
+            }
+        }
+    }
+}
+
+/// 弹窗完成后通知所有匹配的 webhook
+///
+/// 回答、自动回复、过期和失败都会通知。在后台任务中发送，任何失败都只记录日志，不影响弹窗流程
+pub fn dispatch_popup_webhooks(request: &PopupRequest, result: &Result<String, McpToolError>, duration: Duration) {
+    let webhooks = match load_standalone_config() {
+        Ok(config) => config.webhooks,
+        Err(e) => {
+            log_important!(warn, "加载 webhook 配置失败: {}", e);
+            return;
+        }
+    };
+
+    let event = match result {
+        Ok(response) => build_popup_event(request, response, duration),
+        Err(error) => build_popup_failure_event(request, error, duration),
+    };
+    let event_name = event["event"].as_str().unwrap_or_default().to_string();
+    let targets: Vec<WebhookConfig> = webhooks
+        .into_iter()
+        .filter(|webhook| is_subscribed(webhook, &event_name))
+        .collect();
+
+    if targets.is_empty() {
+        return;
+    }
+
+    let body = event.to_string();
+    tokio::spawn(async move {
+        let client = match Client::builder()
+            .timeout(Duration::from_millis(network::WEBHOOK_TIMEOUT_MS))
+            .build()
+        {
+            Ok(client) => client,
+            Err(e) => {
+                log_important!(warn, "创建 webhook 客户端失败: {}", e);
+                return;
+            }
+        };
+
+        for webhook in &targets {
+            deliver_with_retry(&client, webhook, &body).await;
+        }
+    });
+}
+
+/// 向指定地址发送示例事件（用于配置验证，不重试）
+pub async fn send_test_webhook(webhook: &WebhookConfig) -> Result<()> {
+    let body = serde_json::json!({
+        "event": "test",
+        "request_id": crate::mcp::utils::generate_request_id(),
+        "response": { "user_input": "这是一条测试消息", "selected_options": [] },
+        "channel": "test",
+        "duration_ms": 0,
+        "metadata": { "timestamp": chrono::Utc::now().to_rfc3339() }
+    })
+    .to_string();
+
+    let client = Client::builder()
+        .timeout(Duration::from_millis(network::WEBHOOK_TIMEOUT_MS))
+        .build()?;
+    send_webhook(&client, webhook, &body).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sign_payload() {
+        // RFC 4231 测试用例 2
+        let signature = sign_payload("Jefe", "what do ya want for nothing?");
+        assert_eq!(
+            signature,
+            "5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843"
+        );
+    }
+}
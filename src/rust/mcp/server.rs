@@ -92,25 +92,105 @@ impl ServerHandler for ZhiServer {
             "properties": {
                 "message": {
                     "type": "string",
-                    "description": "要显示给用户的消息"
+                    "description": "要显示给用户的消息（使用模板时可省略）"
+                },
+                "template": {
+                    "type": "string",
+                    "description": "弹窗模板名称（可选），模板在寸止配置的 popup_templates 中定义，请求中提供的字段优先于模板"
+                },
+                "allow_multiple": {
+                    "type": "boolean",
+                    "description": "是否允许选择多个选项，默认为true"
+                },
+                "attachments": {
+                    "type": "array",
+                    "description": "随消息展示的附件（可选），单个附件解码后最多5MB，总计最多10MB",
+                    "items": {
+                        "type": "object",
+                        "properties": {
+                            "kind": {
+                                "type": "string",
+                                "enum": ["image"],
+                                "description": "附件类型，目前仅支持 image"
+                            },
+                            "mime": {
+                                "type": "string",
+                                "enum": ["image/png", "image/jpeg", "image/gif", "image/webp"],
+                                "description": "附件 MIME 类型"
+                            },
+                            "data_base64": {
+                                "type": "string",
+                                "description": "Base64 编码的附件内容"
+                            }
+                        },
+                        "required": ["kind", "mime", "data_base64"]
+                    }
                 },
                 "predefined_options": {
                     "type": "array",
                     "items": {"type": "string"},
                     "description": "预定义的选项列表（可选）"
                 },
+                "confirm_options": {
+                    "type": "array",
+                    "items": {"type": "string"},
+                    "description": "需要用户二次确认的选项（可选），必须出现在 predefined_options 中；选择这些选项但未确认的回答会重新弹窗一次，仍未确认则返回 invalid_response 错误"
+                },
                 "is_markdown": {
                     "type": "boolean",
                     "description": "消息是否为Markdown格式，默认为true"
+                },
+                "urgent": {
+                    "type": "boolean",
+                    "description": "是否为紧急请求，紧急请求会根据焦点策略抢占窗口焦点，默认为false"
+                },
+                "expires_at": {
+                    "type": "integer",
+                    "description": "请求过期时间（Unix毫秒时间戳，可选），过期后不再显示弹窗"
+                },
+                "trace_id": {
+                    "type": "string",
+                    "description": "追踪ID（可选），用于关联调用方与弹窗的日志，未提供时自动生成"
+                },
+                "project_path": {
+                    "type": "string",
+                    "description": "当前项目的路径（可选），显示在系统通知中，未提供时使用寸止的工作目录"
+                },
+                "remember_key": {
+                    "type": "string",
+                    "description": "记住回答的键（可选），相同键的请求在有效期内直接复用用户勾选记住的回答，紧急请求除外"
+                },
+                "input_constraints": {
+                    "type": "object",
+                    "description": "自由文本输入约束（可选），等一下提交前和服务端都会校验，不满足时重新弹窗一次，仍不满足则返回 invalid_response 错误",
+                    "properties": {
+                        "pattern": {
+                            "type": "string",
+                            "description": "输入需整体匹配的正则表达式（最多512字符，过于复杂的正则会被拒绝）"
+                        },
+                        "min_len": {
+                            "type": "integer",
+                            "minimum": 0,
+                            "description": "最少字符数"
+                        },
+                        "max_len": {
+                            "type": "integer",
+                            "minimum": 0,
+                            "description": "最多字符数"
+                        },
+                        "placeholder": {
+                            "type": "string",
+                            "description": "输入框占位提示"
+                        }
+                    }
                 }
-            },
-            "required": ["message"]
+            }
         });
 
         if let serde_json::Value::Object(schema_map) = zhi_schema {
             tools.push(Tool {
                 name: Cow::Borrowed("zhi"),
-                description: Some(Cow::Borrowed("智能代码审查交互工具，支持预定义选项、自由文本输入和图片上传")),
+                description: Some(Cow::Borrowed("智能代码审查交互工具，支持预定义选项、自由文本输入和图片上传。失败时错误的 data.error 为错误码：launch_failed（等一下无法启动或异常退出）、transport_error（请求或响应传递失败）、invalid_response（响应不满足输入约束）")),
                 input_schema: Arc::new(schema_map),
                 annotations: None,
             });
@@ -239,6 +319,11 @@ impl ServerHandler for ZhiServer {
 
 /// 启动MCP服务器
 pub async fn run_server() -> Result<(), Box<dyn std::error::Error>> {
+    // 配置了指标地址时启动指标监听
+    if let Ok(metrics_addr) = std::env::var(crate::constants::network::METRICS_ADDR_ENV) {
+        tokio::spawn(crate::mcp::handlers::start_metrics_listener(metrics_addr));
+    }
+
     // 创建并运行服务器
     let service = ZhiServer::new()
         .serve(stdio())
@@ -2,8 +2,16 @@ use anyhow::Result;
 use rmcp::{Error as McpError, model::*};
 
 use crate::mcp::{ZhiRequest, PopupRequest};
-use crate::mcp::handlers::{create_tauri_popup, parse_mcp_response};
-use crate::mcp::utils::{generate_request_id, popup_error};
+use crate::constants::mcp;
+use crate::log_with_trace;
+use crate::mcp::handlers::{
+    check_response_constraints, classify_popup_response, create_tauri_popup, dispatch_popup_webhooks,
+    expand_popup_template, parse_mcp_response, record_popup_result,
+};
+use crate::mcp::utils::{
+    attachment_digest, check_attachments, check_input_constraints, generate_request_id, generate_trace_id, invalid_params_error, invalid_response_error,
+    popup_failure_error, McpToolError,
+};
 
 /// 智能代码审查交互工具
 ///
@@ -13,28 +21,125 @@ pub struct InteractionTool;
 
 impl InteractionTool {
     pub async fn zhi(
-        request: ZhiRequest,
+        mut request: ZhiRequest,
     ) -> Result<CallToolResult, McpError> {
-        let popup_request = PopupRequest {
+        // 展开弹窗模板，引用不存在的模板时直接失败
+        expand_popup_template(&mut request).map_err(invalid_params_error)?;
+        if request.message.trim().is_empty() {
+            return Err(invalid_params_error("message 不能为空（可通过 template 提供）").into());
+        }
+
+        // 在入口处拒绝无效的输入约束（包括过于复杂的正则）
+        if let Some(constraints) = &request.input_constraints {
+            check_input_constraints(constraints)
+                .map_err(|e| invalid_params_error(format!("input_constraints 无效: {}", e)))?;
+        }
+
+        // 需确认的选项必须是预定义选项
+        if let Some(option) = request
+            .confirm_options
+            .iter()
+            .find(|option| !request.predefined_options.contains(option))
+        {
+            return Err(invalid_params_error(format!("confirm_options 中的选项不在 predefined_options 中: {}", option)).into());
+        }
+
+        // 检查附件类型和大小
+        let attachment_data = check_attachments(&request.attachments).map_err(invalid_params_error)?;
+
+        let message = request.message;
+        let mut popup_request = PopupRequest {
             id: generate_request_id(),
-            message: request.message,
+            message: message.clone(),
             predefined_options: if request.predefined_options.is_empty() {
                 None
             } else {
                 Some(request.predefined_options)
             },
             is_markdown: request.is_markdown,
+            urgent: request.urgent,
+            expires_at: request.expires_at,
+            trace_id: Some(request.trace_id.unwrap_or_else(generate_trace_id)),
+            remember_key: request.remember_key,
+            input_constraints: request.input_constraints,
+            allow_multiple: request.allow_multiple.unwrap_or(true),
+            attachments: request.attachments,
+            confirm_options: request.confirm_options,
+            sent_at: Some(chrono::Utc::now().timestamp_millis()),
+            project_path: request.project_path.or_else(|| {
+                std::env::current_dir()
+                    .ok()
+                    .map(|dir| dir.display().to_string())
+            }),
         };
 
-        match create_tauri_popup(&popup_request) {
-            Ok(response) => {
-                // 解析响应内容，支持文本和图片
-                let content = parse_mcp_response(&response)?;
-                Ok(CallToolResult::success(content))
-            }
-            Err(e) => {
-                Err(popup_error(e.to_string()).into())
+        // 审计日志只记录附件摘要和大小，不记录内容
+        for (index, (attachment, data)) in popup_request.attachments.iter().zip(&attachment_data).enumerate() {
+            log_with_trace!(
+                info,
+                popup_request.trace_label(),
+                "附件 {}: mime={}, size={}, sha256={}",
+                index + 1,
+                attachment.mime,
+                data.len(),
+                attachment_digest(data)
+            );
+        }
+
+        let started_at = std::time::Instant::now();
+        let result = Self::resolve_popup(&mut popup_request, &message).await;
+
+        // 所有结果（回答、自动回复、过期、失败）都在这里通知 webhook（后台发送，不阻塞响应）
+        dispatch_popup_webhooks(&popup_request, &result, started_at.elapsed());
+
+        // 解析响应内容，支持文本和图片
+        let content = parse_mcp_response(&result?)?;
+        Ok(CallToolResult::success(content))
+    }
+
+    /// 显示弹窗直到得到满足约束的响应，不满足时重新弹窗一次
+    async fn resolve_popup(popup_request: &mut PopupRequest, message: &str) -> Result<String, McpToolError> {
+        let mut reprompts = 0;
+        loop {
+            let started_at = std::time::Instant::now();
+            // 弹窗会阻塞到用户响应（勿扰/免打扰还可能暂缓），放到阻塞线程中执行，避免占用 tokio 工作线程
+            let blocking_request = popup_request.clone();
+            let result = tokio::task::spawn_blocking(move || create_tauri_popup(&blocking_request))
+                .await
+                .map_err(anyhow::Error::from)
+                .and_then(|result| result);
+            let response = match result {
+                Ok(response) => response,
+                Err(e) => {
+                    record_popup_result(None, started_at.elapsed());
+                    let error = popup_failure_error(e);
+                    log_with_trace!(
+                        warn,
+                        popup_request.trace_label(),
+                        "弹窗失败: id={}, code={}, error={}",
+                        popup_request.id,
+                        error.popup_code().map_or("-", |code| code.as_str()),
+                        error
+                    );
+                    return Err(error);
+                }
+            };
+            record_popup_result(Some(classify_popup_response(&response)), started_at.elapsed());
+
+            // 服务端重新校验输入约束，不满足时重新弹窗一次
+            if let Err(reason) = check_response_constraints(popup_request, &response) {
+                log_with_trace!(warn, popup_request.trace_label(), "用户响应不满足输入约束: id={}, {}", popup_request.id, reason);
+                if reprompts >= mcp::INVALID_RESPONSE_REPROMPTS {
+                    return Err(invalid_response_error(reason));
+                }
+                reprompts += 1;
+                popup_request.id = generate_request_id();
+                popup_request.message = format!("⚠️ 上次回答无效：{}\n\n{}", reason, message);
+                popup_request.remember_key = None;
+                continue;
             }
+
+            return Ok(response);
         }
     }
 }
@@ -3,7 +3,8 @@ use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Deserialize, schemars::JsonSchema)]
 pub struct ZhiRequest {
-    #[schemars(description = "要显示给用户的消息")]
+    #[schemars(description = "要显示给用户的消息（使用模板时可省略）")]
+    #[serde(default)]
     pub message: String,
     #[schemars(description = "预定义的选项列表（可选）")]
     #[serde(default)]
@@ -11,12 +12,77 @@ pub struct ZhiRequest {
     #[schemars(description = "消息是否为Markdown格式，默认为true")]
     #[serde(default = "default_is_markdown")]
     pub is_markdown: bool,
+    #[schemars(description = "是否为紧急请求，紧急请求会根据焦点策略抢占窗口焦点，默认为false")]
+    #[serde(default)]
+    pub urgent: bool,
+    #[schemars(description = "请求过期时间（Unix毫秒时间戳，可选），过期后不再显示弹窗")]
+    #[serde(default)]
+    pub expires_at: Option<i64>,
+    #[schemars(description = "追踪ID（可选），用于关联调用方与弹窗的日志，未提供时自动生成")]
+    #[serde(default)]
+    pub trace_id: Option<String>,
+    #[schemars(description = "记住回答的键（可选），相同键的请求在有效期内直接复用用户勾选记住的回答，紧急请求除外")]
+    #[serde(default)]
+    pub remember_key: Option<String>,
+    #[schemars(description = "自由文本输入约束（可选）：pattern(正则，需整体匹配), min_len, max_len(按字符计), placeholder")]
+    #[serde(default)]
+    pub input_constraints: Option<InputConstraints>,
+    #[schemars(description = "弹窗模板名称（可选），请求中提供的字段优先于模板")]
+    #[serde(default)]
+    pub template: Option<String>,
+    #[schemars(description = "是否允许选择多个选项，默认为true")]
+    #[serde(default)]
+    pub allow_multiple: Option<bool>,
+    #[schemars(description = "随消息展示的附件（可选），目前仅支持图片：kind=\"image\", mime, data_base64")]
+    #[serde(default)]
+    pub attachments: Vec<PopupAttachment>,
+    #[schemars(description = "需要用户二次确认的选项（可选），必须出现在 predefined_options 中，用于删除分支、强制推送等破坏性操作")]
+    #[serde(default)]
+    pub confirm_options: Vec<String>,
+    #[schemars(description = "当前项目的路径（可选），显示在系统通知中，未提供时使用寸止的工作目录")]
+    #[serde(default)]
+    pub project_path: Option<String>,
+}
+
+/// 弹窗附件
+#[derive(Debug, Clone, Serialize, Deserialize, schemars::JsonSchema)]
+pub struct PopupAttachment {
+    #[schemars(description = "附件类型，目前仅支持 image")]
+    pub kind: String,
+    #[schemars(description = "MIME 类型：image/png, image/jpeg, image/gif, image/webp")]
+    pub mime: String,
+    #[schemars(description = "Base64 编码的附件内容")]
+    pub data_base64: String,
+    #[schemars(description = "文件名（可选）")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+}
+
+/// 自由文本输入约束
+#[derive(Debug, Clone, Default, Serialize, Deserialize, schemars::JsonSchema)]
+pub struct InputConstraints {
+    #[schemars(description = "输入需整体匹配的正则表达式")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pattern: Option<String>,
+    #[schemars(description = "最少字符数")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min_len: Option<usize>,
+    #[schemars(description = "最多字符数")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_len: Option<usize>,
+    #[schemars(description = "输入框占位提示")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub placeholder: Option<String>,
 }
 
 fn default_is_markdown() -> bool {
     true
 }
 
+fn default_allow_multiple() -> bool {
+    true
+}
+
 #[derive(Debug, Deserialize, schemars::JsonSchema)]
 pub struct JiyiRequest {
     #[schemars(description = "操作类型：记忆(添加记忆), 回忆(获取项目信息)")]
@@ -45,12 +111,39 @@ fn default_category() -> String {
     "context".to_string()
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PopupRequest {
     pub id: String,
     pub message: String,
     pub predefined_options: Option<Vec<String>>,
     pub is_markdown: bool,
+    #[serde(default)]
+    pub urgent: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expires_at: Option<i64>, // 过期时间（Unix毫秒时间戳）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub trace_id: Option<String>, // 追踪ID，贯穿寸止与等一下的日志
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub remember_key: Option<String>, // 记住回答的键，存在时等一下显示“记住本次选择”
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub input_constraints: Option<InputConstraints>, // 自由文本输入约束
+    #[serde(default = "default_allow_multiple")]
+    pub allow_multiple: bool, // 是否允许选择多个选项
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub attachments: Vec<PopupAttachment>, // 随消息展示的附件
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub confirm_options: Vec<String>, // 选择后需要二次确认的选项
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sent_at: Option<i64>, // 寸止收到工具调用的时间（Unix毫秒时间戳）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub project_path: Option<String>, // 发起请求的项目路径，显示在系统通知中
+}
+
+impl PopupRequest {
+    /// 日志中使用的追踪ID，缺失时回退为请求ID
+    pub fn trace_label(&self) -> &str {
+        self.trace_id.as_deref().unwrap_or(&self.id)
+    }
 }
 
 /// 新的结构化响应数据格式
@@ -59,6 +152,8 @@ pub struct McpResponse {
     pub user_input: Option<String>,
     pub selected_options: Vec<String>,
     pub images: Vec<ImageAttachment>,
+    #[serde(default)]
+    pub attachments: Vec<PopupAttachment>, // 用户随回答发送的文件
     pub metadata: ResponseMetadata,
 }
 
@@ -74,6 +169,14 @@ pub struct ResponseMetadata {
     pub timestamp: Option<String>,
     pub request_id: Option<String>,
     pub source: Option<String>,
+    #[serde(default)]
+    pub remember: bool, // 用户勾选了“记住本次选择”
+    #[serde(default)]
+    pub from_memory: bool, // 响应来自记住的回答，未显示弹窗
+    #[serde(default)]
+    pub answered_at: Option<i64>, // 用户回答的时间（Unix毫秒时间戳）
+    #[serde(default)]
+    pub confirmed: bool, // 用户已二次确认所选的需确认选项
 }
 
 /// 旧格式兼容性支持
@@ -93,6 +196,79 @@ pub struct ImageSource {
     pub data: String,
 }
 
+/// 弹窗流程的错误码，放在工具错误的 `data.error` 中供调用方判断是否重试
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PopupErrorCode {
+    /// 找不到或无法启动等一下，或等一下异常退出
+    LaunchFailed,
+    /// 请求文件读写失败或等一下的输出无法解析
+    TransportError,
+    /// 用户响应不满足输入约束
+    InvalidResponse,
+}
+
+impl PopupErrorCode {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PopupErrorCode::LaunchFailed => "launch_failed",
+            PopupErrorCode::TransportError => "transport_error",
+            PopupErrorCode::InvalidResponse => "invalid_response",
+        }
+    }
+}
+
+/// 带错误码的弹窗错误
+#[derive(Debug, Clone)]
+pub struct PopupError {
+    pub code: PopupErrorCode,
+    pub message: String,
+}
+
+impl PopupError {
+    pub fn new(code: PopupErrorCode, message: impl std::fmt::Display) -> Self {
+        Self { code, message: message.to_string() }
+    }
+}
+
+impl std::fmt::Display for PopupError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.message)
+    }
+}
+
+impl std::error::Error for PopupError {}
+
+/// 等一下进程异常退出时的诊断信息
+#[derive(Debug, Clone, Serialize)]
+pub struct LaunchFailure {
+    pub binary_path: String,
+    pub exit_code: Option<i32>,
+    pub signal: Option<String>, // Unix 信号名或 Windows NTSTATUS 名称
+    pub duration_ms: u64, // 进程运行时长
+    pub stderr_tail: String, // 标准错误输出的最后若干行
+}
+
+impl std::fmt::Display for LaunchFailure {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let status = match (&self.signal, self.exit_code) {
+            (Some(signal), _) => signal.clone(),
+            (None, Some(code)) => format!("exit={}", code),
+            (None, None) => "exit=?".to_string(),
+        };
+        let summary = format!(
+            "{} ({}, {}ms): {}",
+            status,
+            self.binary_path,
+            self.duration_ms,
+            self.stderr_tail.lines().last().unwrap_or_default()
+        );
+        write!(f, "{}", crate::i18n::tr(crate::i18n::Msg::UiProcessFailed, &[&summary]))
+    }
+}
+
+impl std::error::Error for LaunchFailure {}
+
 /// 统一的响应构建函数
 ///
 /// 用于生成标准的JSON响应格式，确保无GUI和有GUI模式输出一致
@@ -109,6 +285,7 @@ pub fn build_mcp_response(
         "images": images,
         "metadata": {
             "timestamp": chrono::Utc::now().to_rfc3339(),
+            "answered_at": chrono::Utc::now().timestamp_millis(),
             "request_id": request_id,
             "source": source
         }
@@ -139,3 +316,24 @@ pub fn build_continue_response(request_id: Option<String>, source: &str) -> Stri
     let response = build_mcp_response(Some(continue_prompt), vec![], vec![], request_id, source);
     response.to_string()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_popup_request_defaults_to_not_urgent() {
+        let json = r#"{"id":"1","message":"测试","predefined_options":null,"is_markdown":true}"#;
+        let request: PopupRequest = serde_json::from_str(json).unwrap();
+
+        assert!(!request.urgent);
+    }
+
+    #[test]
+    fn test_popup_request_urgent_flag() {
+        let json = r#"{"id":"1","message":"测试","predefined_options":null,"is_markdown":true,"urgent":true}"#;
+        let request: PopupRequest = serde_json::from_str(json).unwrap();
+
+        assert!(request.urgent);
+    }
+}
@@ -5,7 +5,10 @@
 use anyhow::Result;
 use std::path::Path;
 use percent_encoding;
-use regex::Regex;
+use regex::{Regex, RegexBuilder};
+
+use crate::constants::mcp;
+use crate::mcp::types::{InputConstraints, PopupAttachment};
 
 /// 解码并规范化路径
 ///
@@ -119,11 +122,207 @@ fn validate_path_format(path: &str) -> Result<()> {
     Ok(())
 }
 
+/// 判断请求是否已过期
+///
+/// 为避免进程间时钟偏差误判，当前时间需超过 `expires_at + skew_ms` 才视为过期
+pub fn is_expired_at(expires_at: Option<i64>, now_ms: i64, skew_ms: u64) -> bool {
+    match expires_at {
+        Some(expires_at) => now_ms > expires_at.saturating_add(skew_ms as i64),
+        None => false,
+    }
+}
+
+/// 编译输入约束的正则表达式，要求整体匹配并限制复杂度
+pub fn compile_input_pattern(pattern: &str) -> Result<Regex, String> {
+    if pattern.len() > mcp::INPUT_PATTERN_MAX_LEN {
+        return Err(format!("正则表达式过长（最多 {} 字符）", mcp::INPUT_PATTERN_MAX_LEN));
+    }
+
+    RegexBuilder::new(&format!("^(?:{})$", pattern))
+        .size_limit(mcp::INPUT_PATTERN_SIZE_LIMIT)
+        .dfa_size_limit(mcp::INPUT_PATTERN_SIZE_LIMIT)
+        .build()
+        .map_err(|e| format!("无效的正则表达式: {}", e))
+}
+
+/// 检查输入约束本身是否有效（在工具入口处调用）
+pub fn check_input_constraints(constraints: &InputConstraints) -> Result<(), String> {
+    if let (Some(min_len), Some(max_len)) = (constraints.min_len, constraints.max_len) {
+        if min_len > max_len {
+            return Err(format!("min_len ({}) 不能大于 max_len ({})", min_len, max_len));
+        }
+    }
+    if let Some(pattern) = &constraints.pattern {
+        compile_input_pattern(pattern)?;
+    }
+    Ok(())
+}
+
+/// 按输入约束校验用户输入，失败时返回面向用户的说明
+pub fn validate_input_constraints(constraints: &InputConstraints, input: &str) -> Result<(), String> {
+    let len = input.chars().count();
+    if let Some(min_len) = constraints.min_len {
+        if len < min_len {
+            return Err(format!("输入至少需要 {} 个字符", min_len));
+        }
+    }
+    if let Some(max_len) = constraints.max_len {
+        if len > max_len {
+            return Err(format!("输入最多 {} 个字符", max_len));
+        }
+    }
+    if let Some(pattern) = &constraints.pattern {
+        if !compile_input_pattern(pattern)?.is_match(input) {
+            return Err(format!("输入格式不符合要求: {}", pattern));
+        }
+    }
+    Ok(())
+}
+
+/// 解码弹窗附件内容
+pub fn decode_attachment(attachment: &PopupAttachment) -> Result<Vec<u8>, String> {
+    use base64::Engine;
+
+    base64::engine::general_purpose::STANDARD
+        .decode(attachment.data_base64.trim())
+        .map_err(|e| format!("附件不是有效的 Base64 数据: {}", e))
+}
+
+/// 在工具入口处检查附件类型和大小，返回解码后的内容
+pub fn check_attachments(attachments: &[PopupAttachment]) -> Result<Vec<Vec<u8>>, String> {
+    let mut decoded = Vec::with_capacity(attachments.len());
+    let mut total = 0;
+
+    for (index, attachment) in attachments.iter().enumerate() {
+        if attachment.kind != mcp::ATTACHMENT_KIND_IMAGE {
+            return Err(format!("附件 {} 类型不支持: {}（目前仅支持 image）", index + 1, attachment.kind));
+        }
+        if !mcp::SUPPORTED_ATTACHMENT_MIME_TYPES.contains(&attachment.mime.as_str()) {
+            return Err(format!(
+                "附件 {} 的 MIME 类型不支持: {}（支持: {}）",
+                index + 1,
+                attachment.mime,
+                mcp::SUPPORTED_ATTACHMENT_MIME_TYPES.join(", ")
+            ));
+        }
+
+        let data = decode_attachment(attachment).map_err(|e| format!("附件 {}: {}", index + 1, e))?;
+        if data.len() > mcp::ATTACHMENT_MAX_BYTES {
+            return Err(format!(
+                "附件 {} 过大: {} 字节（单个附件最多 {} 字节）",
+                index + 1,
+                data.len(),
+                mcp::ATTACHMENT_MAX_BYTES
+            ));
+        }
+        total += data.len();
+        if total > mcp::ATTACHMENTS_TOTAL_MAX_BYTES {
+            return Err(format!("附件总大小超过上限: 最多 {} 字节", mcp::ATTACHMENTS_TOTAL_MAX_BYTES));
+        }
+        decoded.push(data);
+    }
+
+    Ok(decoded)
+}
+
+/// 根据文件内容推断附件 MIME 类型
+pub fn sniff_attachment_mime(data: &[u8]) -> &'static str {
+    if data.starts_with(b"\x89PNG\r\n\x1a\n") {
+        "image/png"
+    } else if data.starts_with(&[0xFF, 0xD8, 0xFF]) {
+        "image/jpeg"
+    } else if data.starts_with(b"GIF87a") || data.starts_with(b"GIF89a") {
+        "image/gif"
+    } else if data.len() >= 12 && &data[..4] == b"RIFF" && &data[8..12] == b"WEBP" {
+        "image/webp"
+    } else if data.starts_with(b"%PDF-") {
+        "application/pdf"
+    } else if std::str::from_utf8(data).is_ok() {
+        "text/plain"
+    } else {
+        "application/octet-stream"
+    }
+}
+
+/// 读取本地文件作为回答附件，超过单个附件大小上限时拒绝
+pub fn read_response_attachment(path: &Path) -> Result<PopupAttachment, String> {
+    use base64::Engine;
+
+    let size = std::fs::metadata(path).map_err(|e| format!("无法读取文件: {}", e))?.len();
+    if size > mcp::ATTACHMENT_MAX_BYTES as u64 {
+        return Err(format!("文件过大: {} 字节（单个附件最多 {} 字节）", size, mcp::ATTACHMENT_MAX_BYTES));
+    }
+
+    let data = std::fs::read(path).map_err(|e| format!("无法读取文件: {}", e))?;
+    let mime = sniff_attachment_mime(&data);
+    let kind = if mime.starts_with("image/") { mcp::ATTACHMENT_KIND_IMAGE } else { mcp::ATTACHMENT_KIND_FILE };
+
+    Ok(PopupAttachment {
+        kind: kind.to_string(),
+        mime: mime.to_string(),
+        data_base64: base64::engine::general_purpose::STANDARD.encode(&data),
+        name: path.file_name().map(|name| name.to_string_lossy().to_string()),
+    })
+}
+
+/// 检查用户回答中附件的大小（回答附件允许任意文件类型）
+pub fn check_response_attachments(attachments: &[PopupAttachment]) -> Result<(), String> {
+    let mut total = 0;
+    for (index, attachment) in attachments.iter().enumerate() {
+        let size = decode_attachment(attachment).map_err(|e| format!("附件 {}: {}", index + 1, e))?.len();
+        if size > mcp::ATTACHMENT_MAX_BYTES {
+            return Err(format!("附件 {} 过大: {} 字节（单个附件最多 {} 字节）", index + 1, size, mcp::ATTACHMENT_MAX_BYTES));
+        }
+        total += size;
+    }
+    if total > mcp::ATTACHMENTS_TOTAL_MAX_BYTES {
+        return Err(format!("附件总大小超过上限: 最多 {} 字节", mcp::ATTACHMENTS_TOTAL_MAX_BYTES));
+    }
+    Ok(())
+}
+
+/// 附件内容摘要（SHA-256 十六进制），用于日志中代替附件数据
+pub fn attachment_digest(data: &[u8]) -> String {
+    ring::digest::digest(&ring::digest::SHA256, data)
+        .as_ref()
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect()
+}
+
+/// 生成追踪 ID（16位十六进制，便于在日志中检索）
+pub fn generate_trace_id() -> String {
+    uuid::Uuid::new_v4().simple().to_string()[..16].to_string()
+}
+
 /// 生成唯一的请求 ID
 pub fn generate_request_id() -> String {
     uuid::Uuid::new_v4().to_string()
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
 
+    #[test]
+    fn test_expiry_respects_clock_skew() {
+        assert!(!is_expired_at(None, 10_000, 0));
+        assert!(!is_expired_at(Some(10_000), 12_000, 5_000));
+        assert!(is_expired_at(Some(10_000), 15_001, 5_000));
+    }
 
+    #[test]
+    fn test_input_constraints_validation() {
+        let constraints = InputConstraints {
+            pattern: Some(r"\d+\.\d+\.\d+".to_string()),
+            min_len: Some(5),
+            max_len: Some(8),
+            placeholder: None,
+        };
 
+        assert!(validate_input_constraints(&constraints, "1.2.3").is_ok());
+        assert!(validate_input_constraints(&constraints, "v1.2.3").is_err());
+        assert!(validate_input_constraints(&constraints, "1.2").is_err());
+        assert!(validate_input_constraints(&constraints, "10.20.300").is_err());
+    }
+}
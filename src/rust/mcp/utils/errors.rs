@@ -4,6 +4,8 @@
 
 use rmcp::Error as McpError;
 
+use crate::mcp::types::{LaunchFailure, PopupError, PopupErrorCode};
+
 /// MCP 错误类型枚举
 #[derive(Debug, thiserror::Error)]
 pub enum McpToolError {
@@ -19,6 +21,18 @@ pub enum McpToolError {
     #[error("记忆管理错误: {0}")]
     Memory(String),
     
+    #[error("参数错误: {0}")]
+    InvalidParams(String),
+    
+    #[error("invalid_response: {0}")]
+    InvalidResponse(String),
+    
+    #[error("{0}")]
+    LaunchFailed(LaunchFailure),
+    
+    #[error("{0}")]
+    Popup(PopupError),
+    
     #[error("IO 错误: {0}")]
     Io(#[from] std::io::Error),
     
@@ -32,9 +46,31 @@ pub enum McpToolError {
 impl From<McpToolError> for McpError {
     fn from(error: McpToolError) -> Self {
         match error {
-            McpToolError::ProjectPath(msg) => {
+            McpToolError::ProjectPath(msg) |
+            McpToolError::InvalidParams(msg) => {
                 McpError::invalid_params(msg, None)
             }
+            McpToolError::InvalidResponse(msg) => {
+                McpError::internal_error(
+                    format!("invalid_response: {}", msg),
+                    Some(serde_json::json!({ "error": PopupErrorCode::InvalidResponse.as_str() })),
+                )
+            }
+            McpToolError::LaunchFailed(failure) => {
+                McpError::internal_error(
+                    failure.to_string(),
+                    Some(serde_json::json!({
+                        "error": PopupErrorCode::LaunchFailed.as_str(),
+                        "launch_failure": failure,
+                    })),
+                )
+            }
+            McpToolError::Popup(error) => {
+                McpError::internal_error(
+                    error.message,
+                    Some(serde_json::json!({ "error": error.code.as_str() })),
+                )
+            }
             McpToolError::PopupCreation(msg) | 
             McpToolError::ResponseParsing(msg) | 
             McpToolError::Memory(msg) => {
@@ -63,6 +99,32 @@ pub fn popup_error(msg: impl Into<String>) -> McpToolError {
     McpToolError::PopupCreation(msg.into())
 }
 
+/// 创建弹窗错误，附带错误码（等一下异常退出时还附带诊断信息）
+///
+/// 未标注错误码的错误按 `transport_error` 处理
+pub fn popup_failure_error(error: anyhow::Error) -> McpToolError {
+    let error = match error.downcast::<LaunchFailure>() {
+        Ok(failure) => return McpToolError::LaunchFailed(failure),
+        Err(error) => error,
+    };
+    match error.downcast::<PopupError>() {
+        Ok(error) => McpToolError::Popup(error),
+        Err(error) => McpToolError::Popup(PopupError::new(PopupErrorCode::TransportError, error)),
+    }
+}
+
+impl McpToolError {
+    /// 弹窗流程错误的错误码
+    pub fn popup_code(&self) -> Option<PopupErrorCode> {
+        match self {
+            McpToolError::LaunchFailed(_) => Some(PopupErrorCode::LaunchFailed),
+            McpToolError::Popup(error) => Some(error.code),
+            McpToolError::InvalidResponse(_) => Some(PopupErrorCode::InvalidResponse),
+            _ => None,
+        }
+    }
+}
+
 /// 创建响应解析错误
 pub fn response_error(msg: impl Into<String>) -> McpToolError {
     McpToolError::ResponseParsing(msg.into())
@@ -72,3 +134,13 @@ pub fn response_error(msg: impl Into<String>) -> McpToolError {
 pub fn memory_error(msg: impl Into<String>) -> McpToolError {
     McpToolError::Memory(msg.into())
 }
+
+/// 创建参数错误
+pub fn invalid_params_error(msg: impl Into<String>) -> McpToolError {
+    McpToolError::InvalidParams(msg.into())
+}
+
+/// 创建用户响应不满足约束的错误
+pub fn invalid_response_error(msg: impl Into<String>) -> McpToolError {
+    McpToolError::InvalidResponse(msg.into())
+}
@@ -4,7 +4,7 @@ use serde::Serialize;
 use teloxide::{
     prelude::*,
     types::{
-        ChatId, InlineKeyboardButton, InlineKeyboardMarkup, KeyboardButton, KeyboardMarkup,
+        ChatId, InlineKeyboardButton, InlineKeyboardMarkup, InputFile, KeyboardButton, KeyboardMarkup,
         MessageId, ParseMode,
     },
     Bot,
@@ -87,6 +87,16 @@ impl TelegramCore {
         Ok(())
     }
 
+    /// 发送图片
+    pub async fn send_photo(&self, data: Vec<u8>, file_name: &str) -> Result<()> {
+        self.bot
+            .send_photo(self.chat_id, InputFile::memory(data).file_name(file_name.to_string()))
+            .await
+            .map_err(|e| anyhow::anyhow!("发送图片失败: {}", e))?;
+
+        Ok(())
+    }
+
     /// 发送选项消息（消息一）
     pub async fn send_options_message(
         &self,
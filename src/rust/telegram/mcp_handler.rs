@@ -3,7 +3,7 @@ use std::collections::HashSet;
 use teloxide::prelude::*;
 
 use crate::config::load_standalone_config;
-use crate::mcp::types::{build_continue_response, build_send_response, PopupRequest};
+use crate::mcp::types::{build_continue_response, build_mcp_response, PopupRequest};
 use crate::telegram::{handle_callback_query, handle_text_message, TelegramCore, TelegramEvent};
 use crate::log_important;
 
@@ -40,6 +40,20 @@ pub async fn handle_telegram_only_mcp_request(request_file: &str) -> Result<()>
         api_url,
     )?;
 
+    // 先发送图片附件，发送失败不影响后续交互
+    for (index, attachment) in request.attachments.iter().enumerate() {
+        let result = match crate::mcp::utils::decode_attachment(attachment) {
+            Ok(data) => {
+                let extension = attachment.mime.trim_start_matches("image/");
+                core.send_photo(data, &format!("attachment_{}.{}", index + 1, extension)).await
+            }
+            Err(e) => Err(anyhow::anyhow!(e)),
+        };
+        if let Err(e) = result {
+            log_important!(warn, "发送附件 {} 到Telegram失败: {}", index + 1, e);
+        }
+    }
+
     // 发送消息到Telegram
     let predefined_options = request.predefined_options.clone().unwrap_or_default();
 
@@ -52,6 +66,7 @@ pub async fn handle_telegram_only_mcp_request(request_file: &str) -> Result<()>
 
     // 发送操作消息（假设启用继续回复）
     core.send_operation_message(true).await?;
+    crate::app::mark_ui_launched();
 
     // 启动消息监听循环
     start_telegram_mcp_listener(core, request, predefined_options).await
@@ -67,6 +82,7 @@ async fn start_telegram_mcp_listener(
     let mut selected_options: HashSet<String> = HashSet::new();
     let mut user_input = String::new();
     let mut options_message_id: Option<i32> = None;
+    let mut confirm_pending: Option<Vec<String>> = None;
 
     // 获取当前最新的消息ID作为基准
     if let Ok(updates) = core.bot.get_updates().limit(10).await {
@@ -103,6 +119,7 @@ async fn start_telegram_mcp_listener(
                                 &mut options_message_id,
                                 &mut user_input,
                                 &selected_options,
+                                &mut confirm_pending,
                                 &request,
                             ).await {
                                 if let Some(_result) = e.downcast_ref::<ProcessingComplete>() {
@@ -173,6 +190,7 @@ async fn handle_message_update(
     options_message_id: &mut Option<i32>,
     user_input: &mut String,
     selected_options: &HashSet<String>,
+    confirm_pending: &mut Option<Vec<String>>,
     request: &PopupRequest,
 ) -> Result<()> {
     // 识别选项消息ID
@@ -182,8 +200,9 @@ async fn handle_message_update(
     if let Ok(Some(event)) = handle_text_message(message, core.chat_id, None).await {
         match event {
             TelegramEvent::SendPressed => {
-                handle_send_pressed(core, selected_options, user_input, request).await?;
-                return Err(ProcessingComplete.into());
+                if handle_send_pressed(core, selected_options, user_input, confirm_pending, request).await? {
+                    return Err(ProcessingComplete.into());
+                }
             }
             TelegramEvent::ContinuePressed => {
                 handle_continue_pressed(core, request).await?;
@@ -234,15 +253,36 @@ fn identify_options_message_id(
     }
 }
 
-/// 处理发送按钮按下
+/// 处理发送按钮按下，返回是否已完成响应
+///
+/// 选择了需确认的选项时，第一次按下只发送确认提示，选项不变再次按下才发送响应
 async fn handle_send_pressed(
     core: &TelegramCore,
     selected_options: &HashSet<String>,
     user_input: &str,
+    confirm_pending: &mut Option<Vec<String>>,
     request: &PopupRequest,
-) -> Result<()> {
+) -> Result<bool> {
     // 使用统一的响应构建函数
-    let selected_list: Vec<String> = selected_options.iter().cloned().collect();
+    let mut selected_list: Vec<String> = selected_options.iter().cloned().collect();
+    selected_list.sort();
+
+    let confirm_required: Vec<String> = selected_list
+        .iter()
+        .filter(|option| request.confirm_options.contains(option))
+        .cloned()
+        .collect();
+    let confirmed = !confirm_required.is_empty();
+    if confirmed && confirm_pending.as_ref() != Some(&selected_list) {
+        *confirm_pending = Some(selected_list);
+        let _ = core
+            .send_message(&format!(
+                "⚠️ 以下选项需要确认：{}\n再次点击发送以确认",
+                confirm_required.join("、")
+            ))
+            .await;
+        return Ok(false);
+    }
 
     let user_input_option = if user_input.is_empty() {
         None
@@ -250,16 +290,17 @@ async fn handle_send_pressed(
         Some(user_input.to_string())
     };
 
-    let response = build_send_response(
+    let mut response = build_mcp_response(
         user_input_option,
         selected_list.clone(),
         vec![], // 无GUI模式下没有图片
         Some(request.id.clone()),
         "telegram",
     );
+    response["metadata"]["confirmed"] = serde_json::json!(confirmed);
 
     // 输出JSON响应到stdout（MCP协议要求）
-    println!("{}", response);
+    crate::app::emit_mcp_response(&response.to_string())?;
 
     // 发送确认消息（使用统一的反馈消息生成函数）
     let feedback_message = crate::telegram::core::build_feedback_message(
@@ -269,7 +310,7 @@ async fn handle_send_pressed(
     );
     let _ = core.send_message(&feedback_message).await;
 
-    Ok(())
+    Ok(true)
 }
 
 /// 处理继续按钮按下
@@ -284,7 +325,7 @@ async fn handle_continue_pressed(
     );
 
     // 输出JSON响应到stdout（MCP协议要求）
-    println!("{}", response);
+    crate::app::emit_mcp_response(&response)?;
 
     // 发送确认消息（使用统一的反馈消息生成函数）
     let feedback_message = crate::telegram::core::build_feedback_message(
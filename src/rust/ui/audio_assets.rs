@@ -286,6 +286,14 @@ pub fn get_audio_asset_manager() -> &'static std::sync::Mutex<AudioAssetManager>
     AUDIO_ASSET_MANAGER.get_or_init(|| std::sync::Mutex::new(AudioAssetManager::new()))
 }
 
+/// 统计内嵌音频资源数量（不依赖应用实例，供自检使用）
+pub fn embedded_audio_asset_count() -> usize {
+    let manager = AudioAssetManager::new();
+    EmbeddedAudio::iter()
+        .filter(|file_path| manager.is_audio_file(file_path.as_ref()))
+        .count()
+}
+
 /// 初始化音频资源管理器
 pub fn initialize_audio_asset_manager(app: &AppHandle) -> Result<()> {
     let manager = get_audio_asset_manager();
@@ -6,7 +6,13 @@ use tauri::{AppHandle, Manager, State};
 
 #[tauri::command]
 pub async fn get_app_info() -> Result<String, String> {
-    Ok(format!("寸止 v{}", env!("CARGO_PKG_VERSION")))
+    Ok(format!("寸止 v{}", crate::constants::app::get_build_info().version_with_hash()))
+}
+
+/// 获取构建信息（用于关于面板和问题反馈）
+#[tauri::command]
+pub async fn get_build_info() -> Result<crate::constants::app::BuildInfo, String> {
+    Ok(crate::constants::app::get_build_info())
 }
 
 #[tauri::command]
@@ -368,10 +374,17 @@ pub async fn set_window_settings(
     Ok(())
 }
 
+/// 读取本地文件作为回答附件（推断 MIME 类型并检查大小）
+#[tauri::command]
+pub async fn attach_file_to_response(path: String) -> Result<crate::mcp::types::PopupAttachment, String> {
+    crate::mcp::utils::read_response_attachment(std::path::Path::new(&path))
+}
+
 #[tauri::command]
 pub async fn send_mcp_response(
     response: serde_json::Value,
     state: State<'_, AppState>,
+    app: AppHandle,
 ) -> Result<(), String> {
     // 将响应序列化为JSON字符串
     let response_str =
@@ -381,14 +394,19 @@ pub async fn send_mcp_response(
         return Err("响应内容不能为空".to_string());
     }
 
+    // 检查回答附件大小
+    if let Some(attachments) = response.get("attachments") {
+        let attachments: Vec<crate::mcp::types::PopupAttachment> =
+            serde_json::from_value(attachments.clone()).map_err(|e| format!("附件格式无效: {}", e))?;
+        crate::mcp::utils::check_response_attachments(&attachments)?;
+    }
+
     // 检查是否为MCP模式
-    let args: Vec<String> = std::env::args().collect();
-    let is_mcp_mode = args.len() >= 3 && args[1] == "--mcp-request";
+    let is_mcp_mode = crate::app::is_mcp_request_mode();
 
     if is_mcp_mode {
-        // MCP模式：直接输出到stdout（MCP协议要求）
-        println!("{}", response_str);
-        std::io::Write::flush(&mut std::io::stdout())
+        // MCP模式：输出到stdout（MCP协议要求），并记录退出码
+        crate::app::emit_mcp_response(&response_str)
             .map_err(|e| format!("刷新stdout失败: {}", e))?;
     } else {
         // 通过channel发送响应（如果有的话）
@@ -405,19 +423,28 @@ pub async fn send_mcp_response(
         }
     }
 
+    // 提交成功后删除该请求的草稿
+    if let Some(request_id) = response["metadata"]["request_id"].as_str() {
+        if let Err(e) = crate::ui::drafts::discard_response_draft(request_id) {
+            log::warn!("删除回复草稿失败: {}", e);
+        }
+    }
+
+    // 响应或取消后请求不再处于待处理状态
+    crate::ui::pending::mark_request_resolved(&app);
+
     Ok(())
 }
 
 #[tauri::command]
 pub fn get_cli_args() -> Result<serde_json::Value, String> {
-    let args: Vec<String> = std::env::args().collect();
     let mut result = serde_json::Map::new();
 
     // 检查是否有 --mcp-request 参数
-    if args.len() >= 3 && args[1] == "--mcp-request" {
+    if let Some(request_file) = crate::app::mcp_request_file() {
         result.insert(
             "mcp_request".to_string(),
-            serde_json::Value::String(args[2].clone()),
+            serde_json::Value::String(request_file),
         );
     }
 
@@ -529,11 +556,12 @@ pub async fn create_test_popup(request: serde_json::Value) -> Result<String, Str
     let popup_request: PopupRequest = serde_json::from_value(request)
         .map_err(|e| format!("解析请求参数失败: {}", e))?;
 
-    // 调用现有的popup创建函数
-    match create_tauri_popup(&popup_request) {
-        Ok(response) => Ok(response),
-        Err(e) => Err(format!("创建测试popup失败: {}", e))
-    }
+    // 调用现有的popup创建函数（会阻塞到用户响应，放到阻塞线程中执行）
+    tokio::task::spawn_blocking(move || create_tauri_popup(&popup_request))
+        .await
+        .map_err(anyhow::Error::from)
+        .and_then(|result| result)
+        .map_err(|e| format!("创建测试popup失败: {}", e))
 }
 
 // 自定义prompt相关命令
@@ -774,6 +802,14 @@ pub async fn get_config_file_path(app: AppHandle) -> Result<String, String> {
     Ok(normalized_path)
 }
 
+/// 获取当前生效的配置文件路径（包括 `--config` 或 `CUNZHI_CONFIG_PATH` 指定的路径）
+#[tauri::command]
+pub async fn get_config_path() -> Result<String, String> {
+    let config_path = crate::config::get_standalone_config_path()
+        .map_err(|e| format!("获取配置文件路径失败: {}", e))?;
+    Ok(normalize_path_display(&config_path))
+}
+
 /// 跨平台路径显示规范化
 fn normalize_path_display(path: &std::path::Path) -> String {
     // 如果文件存在，尝试获取规范路径
@@ -0,0 +1,84 @@
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+
+use crate::config::get_standalone_config_path;
+use crate::constants::{app, mcp};
+use crate::log_debug;
+use crate::utils::{with_file_lock, write_file_atomic};
+
+/// 未提交的回复草稿
+#[derive(Debug, Serialize, Deserialize, Clone)]
+struct ResponseDraft {
+    text: String,
+    updated_at: i64, // 最后保存时间（Unix毫秒时间戳）
+}
+
+/// 草稿文件路径，与配置文件同目录
+fn drafts_path() -> Result<PathBuf> {
+    Ok(get_standalone_config_path()?.with_file_name(app::RESPONSE_DRAFTS_FILE_NAME))
+}
+
+/// 读取未过期的草稿
+fn load_drafts() -> Result<HashMap<String, ResponseDraft>> {
+    let path = drafts_path()?;
+    if !path.exists() {
+        return Ok(HashMap::new());
+    }
+
+    let mut drafts: HashMap<String, ResponseDraft> = serde_json::from_str(&fs::read_to_string(&path)?)?;
+    let expire_before = chrono::Utc::now().timestamp_millis() - (mcp::RESPONSE_DRAFT_TTL_SECS as i64) * 1000;
+    drafts.retain(|_, draft| draft.updated_at >= expire_before);
+    Ok(drafts)
+}
+
+fn save_drafts(drafts: &HashMap<String, ResponseDraft>) -> Result<()> {
+    write_file_atomic(&drafts_path()?, serde_json::to_string_pretty(drafts)?)?;
+    Ok(())
+}
+
+/// 持有文件锁读取、修改并保存草稿，多个等一下进程同时保存时不会丢失彼此的草稿
+fn update_drafts<T>(update: impl FnOnce(&mut HashMap<String, ResponseDraft>) -> T) -> Result<T> {
+    with_file_lock(&drafts_path()?, || {
+        let mut drafts = load_drafts()?;
+        let result = update(&mut drafts);
+        save_drafts(&drafts)?;
+        Ok(result)
+    })
+}
+
+/// 删除指定请求的草稿（提交成功后调用）
+pub fn discard_response_draft(request_id: &str) -> Result<()> {
+    if update_drafts(|drafts| drafts.remove(request_id).is_some())? {
+        log_debug!("已删除回复草稿: {}", request_id);
+    }
+    Ok(())
+}
+
+/// 保存回复草稿，内容为空时删除草稿
+#[tauri::command]
+pub async fn save_response_draft(request_id: String, text: String) -> Result<(), String> {
+    update_drafts(|drafts| {
+        if text.trim().is_empty() {
+            drafts.remove(&request_id);
+        } else {
+            drafts.insert(
+                request_id,
+                ResponseDraft {
+                    text,
+                    updated_at: chrono::Utc::now().timestamp_millis(),
+                },
+            );
+        }
+    })
+    .map_err(|e| format!("保存草稿失败: {}", e))
+}
+
+/// 获取回复草稿
+#[tauri::command]
+pub async fn get_response_draft(request_id: String) -> Result<Option<String>, String> {
+    let drafts = load_drafts().map_err(|e| format!("读取草稿失败: {}", e))?;
+    Ok(drafts.get(&request_id).map(|draft| draft.text.clone()))
+}
@@ -1,6 +1,7 @@
 use crate::config::AppState;
 use crate::constants::app::{EXIT_CONFIRMATION_WINDOW_SECS, REQUIRED_EXIT_ATTEMPTS};
 use crate::log_important;
+use crate::i18n::{format_duration, tr, Msg};
 use std::time::{Duration, Instant};
 use tauri::{AppHandle, Manager, State, Emitter};
 
@@ -112,9 +113,9 @@ pub async fn handle_system_exit_request(
         Ok(true)
     } else if show_warning {
         // 发送警告消息到前端
-        let warning_message = format!(
-            "再次按下退出快捷键以确认退出 ({}秒内有效)",
-            EXIT_CONFIRMATION_WINDOW_SECS
+        let warning_message = tr(
+            Msg::ExitConfirmation,
+            &[&format_duration(EXIT_CONFIRMATION_WINDOW_SECS)],
         );
 
         if let Some(window) = app.get_webview_window("main") {
@@ -137,6 +138,9 @@ pub async fn handle_system_exit_request(
 
 /// 执行实际的退出操作
 async fn perform_exit(app: AppHandle) -> Result<(), String> {
+    // 退出前按顺序清理
+    let exit_code = crate::ui::shutdown::graceful_shutdown(&app).await;
+
     // 关闭所有窗口
     if let Some(window) = app.get_webview_window("main") {
         let _ = window.close();
@@ -144,7 +148,7 @@ async fn perform_exit(app: AppHandle) -> Result<(), String> {
     
     // 短暂延迟后强制退出应用
     tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
-    app.exit(0);
+    app.exit(exit_code);
     Ok(())
 }
 
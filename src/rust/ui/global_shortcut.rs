@@ -0,0 +1,201 @@
+use std::fs::{File, OpenOptions, TryLockError};
+use std::sync::OnceLock;
+
+use tauri::{AppHandle, Manager, State};
+use tauri_plugin_global_shortcut::{GlobalShortcutExt, Shortcut, ShortcutEvent, ShortcutState};
+use tauri_plugin_notification::NotificationExt;
+
+use crate::config::{load_standalone_config, save_config, AppState};
+use crate::constants::app;
+use crate::utils::{count_pending_markers, get_runtime_dir};
+use crate::{log_debug, log_important};
+
+/// 全局快捷键所有权锁，持有期间由本进程负责注册快捷键
+///
+/// 每个弹窗都是独立的等一下进程，同一快捷键只能注册一次，因此只由持有锁的进程注册，
+/// 其他进程等待锁释放（持有者退出）后接管
+static SHORTCUT_OWNER_LOCK: OnceLock<File> = OnceLock::new();
+
+/// 全局快捷键状态
+#[derive(Debug, serde::Serialize, serde::Deserialize, Clone)]
+pub struct GlobalShortcutStatus {
+    pub accelerator: String,
+    pub registered: bool,
+    pub error: Option<String>,
+}
+
+/// 所有寸止进程中是否有待处理的请求
+fn has_pending_request() -> bool {
+    count_pending_markers().is_ok_and(|count| count > 0)
+}
+
+/// 当前进程是否负责注册全局快捷键
+fn is_shortcut_owner() -> bool {
+    SHORTCUT_OWNER_LOCK.get().is_some()
+}
+
+/// 全局快捷键触发处理
+pub fn handle_global_shortcut(app: &AppHandle, _shortcut: &Shortcut, event: ShortcutEvent) {
+    if event.state() != ShortcutState::Pressed {
+        return;
+    }
+
+    if has_pending_request() {
+        if let Err(e) = crate::ui::focus_pending_popup(app) {
+            log_important!(warn, "全局快捷键唤起窗口失败: {}", e);
+        }
+    } else if let Err(e) = app
+        .notification()
+        .builder()
+        .title(app::NAME)
+        .body("没有待处理的请求")
+        .show()
+    {
+        log_important!(warn, "发送系统通知失败: {}", e);
+    }
+}
+
+/// 注册全局快捷键，空字符串表示不注册
+fn register_accelerator(app: &AppHandle, accelerator: &str) -> Result<(), String> {
+    if accelerator.trim().is_empty() {
+        return Ok(());
+    }
+
+    let shortcut: Shortcut = accelerator
+        .parse()
+        .map_err(|e| format!("无效的快捷键 {}: {}", accelerator, e))?;
+
+    app.global_shortcut()
+        .register(shortcut)
+        .map_err(|e| format!("注册全局快捷键 {} 失败（可能与其他应用冲突）: {}", accelerator, e))
+}
+
+/// 记录全局快捷键注册结果
+fn record_shortcut_error(app: &AppHandle, error: Option<String>) {
+    let state = app.state::<AppState>();
+    if let Ok(mut guard) = state.global_shortcut_error.lock() {
+        *guard = error;
+    };
+}
+
+/// 应用启动时争取全局快捷键所有权（失败只记录日志，不影响启动）
+///
+/// 取得所有权后立即注册，否则在后台等待持有者退出后接管
+pub fn setup_global_shortcut(app: &AppHandle) {
+    let lock_file = match open_shortcut_lock_file() {
+        Ok(file) => file,
+        Err(e) => {
+            log_important!(warn, "{}", e);
+            return;
+        }
+    };
+
+    match lock_file.try_lock() {
+        Ok(()) => become_shortcut_owner(app, lock_file),
+        Err(TryLockError::WouldBlock) => {
+            log_debug!("全局快捷键由其他等一下进程注册，等待其退出后接管");
+            let app = app.clone();
+            std::thread::spawn(move || match lock_file.lock() {
+                Ok(()) => become_shortcut_owner(&app, lock_file),
+                Err(e) => log_important!(warn, "等待全局快捷键所有权失败: {}", e),
+            });
+        }
+        Err(TryLockError::Error(e)) => log_important!(warn, "获取全局快捷键所有权失败: {}", e),
+    }
+}
+
+/// 打开运行时目录中的全局快捷键锁文件
+fn open_shortcut_lock_file() -> Result<File, String> {
+    let path = get_runtime_dir()
+        .map_err(|e| format!("获取运行时目录失败: {}", e))?
+        .join(app::GLOBAL_SHORTCUT_LOCK_FILE);
+    OpenOptions::new()
+        .create(true)
+        .truncate(false)
+        .write(true)
+        .open(&path)
+        .map_err(|e| format!("打开全局快捷键锁文件失败: {}", e))
+}
+
+/// 取得所有权后注册配置中的全局快捷键
+fn become_shortcut_owner(app: &AppHandle, lock_file: File) {
+    let _ = SHORTCUT_OWNER_LOCK.set(lock_file);
+    log_debug!("已取得全局快捷键所有权");
+
+    // 等待期间其他进程可能修改了快捷键，重新读取配置文件
+    let accelerator = match load_standalone_config() {
+        Ok(config) => config.ui_config.global_shortcut,
+        Err(e) => {
+            log_important!(warn, "加载配置失败: {}", e);
+            return;
+        }
+    };
+
+    match register_accelerator(app, &accelerator) {
+        Ok(()) => record_shortcut_error(app, None),
+        Err(e) => {
+            log_important!(warn, "{}", e);
+            record_shortcut_error(app, Some(e));
+        }
+    }
+}
+
+#[tauri::command]
+pub async fn get_global_shortcut(state: State<'_, AppState>, app: AppHandle) -> Result<GlobalShortcutStatus, String> {
+    let accelerator = {
+        let config = state.config.lock().map_err(|e| format!("获取配置失败: {}", e))?;
+        config.ui_config.global_shortcut.clone()
+    };
+    let error = state
+        .global_shortcut_error
+        .lock()
+        .map_err(|e| format!("获取快捷键状态失败: {}", e))?
+        .clone();
+
+    let registered = accelerator
+        .parse::<Shortcut>()
+        .map(|shortcut| app.global_shortcut().is_registered(shortcut))
+        .unwrap_or(false);
+
+    Ok(GlobalShortcutStatus {
+        accelerator,
+        registered,
+        error,
+    })
+}
+
+#[tauri::command]
+pub async fn set_global_shortcut(accel: String, state: State<'_, AppState>, app: AppHandle) -> Result<(), String> {
+    let previous = {
+        let config = state.config.lock().map_err(|e| format!("获取配置失败: {}", e))?;
+        config.ui_config.global_shortcut.clone()
+    };
+
+    if is_shortcut_owner() {
+        // 先注销旧的快捷键
+        if let Ok(shortcut) = previous.parse::<Shortcut>() {
+            let _ = app.global_shortcut().unregister(shortcut);
+        }
+
+        if let Err(e) = register_accelerator(&app, &accel) {
+            // 新快捷键注册失败时恢复旧的快捷键
+            let _ = register_accelerator(&app, &previous);
+            record_shortcut_error(&app, Some(e.clone()));
+            return Err(e);
+        }
+        record_shortcut_error(&app, None);
+    } else if !accel.trim().is_empty() {
+        // 由其他进程注册时只校验格式，下一个取得所有权的进程使用新配置
+        accel
+            .parse::<Shortcut>()
+            .map_err(|e| format!("无效的快捷键 {}: {}", accel, e))?;
+    }
+
+    {
+        let mut config = state.config.lock().map_err(|e| format!("获取配置失败: {}", e))?;
+        config.ui_config.global_shortcut = accel;
+    }
+
+    save_config(&state, &app).await.map_err(|e| format!("保存配置失败: {}", e))?;
+    Ok(())
+}
@@ -7,6 +7,11 @@ pub mod updater;
 pub mod exit;
 pub mod window_events;
 pub mod exit_handler;
+pub mod notification;
+pub mod global_shortcut;
+pub mod pending;
+pub mod shutdown;
+pub mod drafts;
 
 pub use commands::*;
 pub use window::*;
@@ -16,3 +21,8 @@ pub use updater::*;
 pub use exit::*;
 pub use window_events::*;
 pub use exit_handler::*;
+pub use notification::*;
+pub use global_shortcut::*;
+pub use pending::*;
+pub use shutdown::*;
+pub use drafts::*;
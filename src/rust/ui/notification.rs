@@ -0,0 +1,160 @@
+use notify_rust::{Notification, NotificationResponse};
+use tauri::{AppHandle, Emitter, Manager, State};
+
+use crate::config::{AppState, save_config};
+use crate::constants::{app, window};
+use crate::mcp::types::PopupRequest;
+use crate::log_important;
+
+/// 生成通知中使用的消息预览
+pub fn build_message_preview(message: &str) -> String {
+    // 将多行内容压缩为单行，避免系统通知显示错乱
+    let single_line = message.split_whitespace().collect::<Vec<_>>().join(" ");
+
+    if single_line.chars().count() > window::NOTIFICATION_PREVIEW_MAX_CHARS {
+        let truncated: String = single_line
+            .chars()
+            .take(window::NOTIFICATION_PREVIEW_MAX_CHARS)
+            .collect();
+        format!("{}...", truncated)
+    } else {
+        single_line
+    }
+}
+
+/// 生成通知正文：消息预览，有项目路径时另起一行显示
+fn build_notification_body(request: &PopupRequest) -> String {
+    let preview = build_message_preview(&request.message);
+    match request.project_path.as_deref().filter(|path| !path.trim().is_empty()) {
+        Some(path) => format!("{}\n项目: {}", preview, path),
+        None => preview,
+    }
+}
+
+/// 主窗口当前是否处于焦点状态
+fn is_main_window_focused(app: &AppHandle) -> bool {
+    app.get_webview_window("main")
+        .and_then(|window| window.is_focused().ok())
+        .unwrap_or(false)
+}
+
+/// 收到弹窗请求时发送系统通知
+///
+/// 未启用通知或窗口已获得焦点时不发送
+pub fn notify_popup_request(app: &AppHandle, request: &PopupRequest) -> Result<(), String> {
+    let enabled = {
+        let state = app.state::<AppState>();
+        let config = state.config.lock().map_err(|e| format!("获取配置失败: {}", e))?;
+        config.ui_config.notify_on_request
+    };
+
+    if !enabled || is_main_window_focused(app) {
+        return Ok(());
+    }
+
+    show_clickable_notification(
+        app,
+        &format!("{} - 新的请求", app::NAME),
+        &build_notification_body(request),
+    )?;
+
+    log_important!(info, "已发送请求通知: {}", request.id);
+    Ok(())
+}
+
+/// 发送系统通知，点击通知正文时聚焦主窗口
+///
+/// 通知插件不提供点击回调，这里直接使用 notify-rust 并在后台线程等待用户响应
+fn show_clickable_notification(app: &AppHandle, title: &str, body: &str) -> Result<(), String> {
+    let mut notification = Notification::new();
+    notification.summary(title).body(body).auto_icon();
+
+    // XDG 需要声明 default 动作，点击通知正文时才会回传
+    #[cfg(all(unix, not(target_os = "macos")))]
+    notification.action("default", "查看");
+
+    // 开发模式下没有注册 AppUserModelID，沿用默认值
+    #[cfg(windows)]
+    if !tauri::is_dev() {
+        notification.app_id(&app.config().identifier);
+    }
+
+    #[cfg(target_os = "macos")]
+    {
+        let _ = notify_rust::set_application(if tauri::is_dev() {
+            "com.apple.Terminal"
+        } else {
+            &app.config().identifier
+        });
+    }
+
+    let handle = notification
+        .show()
+        .map_err(|e| format!("发送系统通知失败: {}", e))?;
+
+    let app = app.clone();
+    std::thread::spawn(move || {
+        let result = handle.wait_for_response(|response: &NotificationResponse| {
+            if matches!(response, NotificationResponse::Default) {
+                if let Err(e) = focus_main_window(&app) {
+                    log_important!(warn, "点击通知后聚焦窗口失败: {}", e);
+                }
+            }
+        });
+        if let Err(e) = result {
+            log_important!(warn, "等待通知响应失败: {}", e);
+        }
+    });
+
+    Ok(())
+}
+
+/// 将主窗口带到前台并通知前端
+pub fn focus_main_window(app: &AppHandle) -> Result<(), String> {
+    if let Some(main_window) = app.get_webview_window("main") {
+        let _ = main_window.unminimize();
+        main_window
+            .set_focus()
+            .map_err(|e| format!("设置窗口焦点失败: {}", e))?;
+    }
+
+    if let Err(e) = app.emit("popup-focus-requested", ()) {
+        log_important!(warn, "发送窗口聚焦事件失败: {}", e);
+    }
+
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn get_notify_on_request(state: State<'_, AppState>) -> Result<bool, String> {
+    let config = state.config.lock().map_err(|e| format!("获取配置失败: {}", e))?;
+    Ok(config.ui_config.notify_on_request)
+}
+
+#[tauri::command]
+pub async fn set_notify_on_request(
+    enabled: bool,
+    state: State<'_, AppState>,
+    app: AppHandle,
+) -> Result<(), String> {
+    {
+        let mut config = state.config.lock().map_err(|e| format!("获取配置失败: {}", e))?;
+        config.ui_config.notify_on_request = enabled;
+    }
+
+    // 保存配置到文件
+    save_config(&state, &app).await.map_err(|e| format!("保存配置失败: {}", e))?;
+    Ok(())
+}
+
+/// 前端显示弹窗时调用，按配置发送系统通知
+#[tauri::command]
+pub async fn send_request_notification(request: PopupRequest, app: AppHandle) -> Result<(), String> {
+    notify_popup_request(&app, &request)
+}
+
+/// 聚焦主窗口（需要唤起窗口时调用）
+#[tauri::command]
+pub async fn focus_popup_window(app: AppHandle) -> Result<(), String> {
+    focus_main_window(&app)
+}
@@ -0,0 +1,204 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Mutex, OnceLock};
+
+use notify::{EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::config::AppState;
+use crate::constants::app;
+use crate::utils::{count_pending_markers, get_runtime_dir, oldest_pending_request_id};
+use crate::{log_debug, log_important};
+
+/// 待处理请求窗口标题前缀
+const PENDING_TITLE_PREFIX: &str = "等一下";
+
+/// 运行时目录监听器，需要在进程存活期间一直持有
+static PENDING_MARKER_WATCHER: OnceLock<Mutex<RecommendedWatcher>> = OnceLock::new();
+
+/// 本进程的请求是否已处理完成
+///
+/// 寸止在等一下退出后才删除标记文件，处理完成后统计时需要排除本进程的请求
+static REQUEST_RESOLVED: AtomicBool = AtomicBool::new(false);
+
+/// 本进程处理的请求ID
+static OWN_REQUEST_ID: OnceLock<String> = OnceLock::new();
+
+/// 统计所有寸止进程的待处理请求数量
+fn current_pending_count() -> Result<u32, String> {
+    let count = count_pending_markers().map_err(|e| format!("统计待处理请求失败: {}", e))?;
+    let own_resolved = REQUEST_RESOLVED.load(Ordering::SeqCst) as usize;
+    Ok(count.saturating_sub(own_resolved) as u32)
+}
+
+/// 重新统计待处理请求数量，变化时同步到窗口标题、徽标和前端
+fn refresh_pending_count(app: &AppHandle) -> Result<u32, String> {
+    let count = current_pending_count()?;
+    {
+        let state = app.state::<AppState>();
+        let mut last_count = state
+            .pending_request_count
+            .lock()
+            .map_err(|e| format!("获取待处理请求计数失败: {}", e))?;
+        if *last_count == count {
+            return Ok(count);
+        }
+        *last_count = count;
+    }
+
+    if let Some(window) = app.get_webview_window("main") {
+        let title = if count > 0 {
+            format!("{} ({})", PENDING_TITLE_PREFIX, count)
+        } else {
+            app::get_app_info().get_window_title()
+        };
+        if let Err(e) = window.set_title(&title) {
+            log_important!(warn, "更新窗口标题失败: {}", e);
+        }
+
+        // macOS 显示 Dock 徽标
+        #[cfg(target_os = "macos")]
+        if let Err(e) = window.set_badge_count(if count > 0 { Some(count as i64) } else { None }) {
+            log_important!(warn, "更新Dock徽标失败: {}", e);
+        }
+
+        // Windows 不支持徽标数字，在任务栏图标上叠加提示图标
+        #[cfg(target_os = "windows")]
+        if let Err(e) = window.set_overlay_icon(if count > 0 { Some(pending_overlay_icon()) } else { None }) {
+            log_important!(warn, "更新任务栏叠加图标失败: {}", e);
+        }
+    }
+
+    if let Err(e) = app.emit("pending_count_changed", count) {
+        log_important!(warn, "发送待处理请求计数事件失败: {}", e);
+    }
+
+    Ok(count)
+}
+
+/// 生成任务栏叠加图标（红色圆点）
+#[cfg(target_os = "windows")]
+fn pending_overlay_icon() -> tauri::image::Image<'static> {
+    const SIZE: u32 = 16;
+    let center = (SIZE as f32 - 1.0) / 2.0;
+    let radius = SIZE as f32 / 2.0;
+
+    let mut rgba = Vec::with_capacity((SIZE * SIZE * 4) as usize);
+    for y in 0..SIZE {
+        for x in 0..SIZE {
+            let (dx, dy) = (x as f32 - center, y as f32 - center);
+            let alpha = if dx * dx + dy * dy <= radius * radius { 0xFF } else { 0 };
+            rgba.extend_from_slice(&[0xE5, 0x39, 0x35, alpha]);
+        }
+    }
+    tauri::image::Image::new_owned(rgba, SIZE, SIZE)
+}
+
+/// 本进程的请求是否仍在等待响应
+fn has_own_pending_request() -> bool {
+    OWN_REQUEST_ID.get().is_some() && !REQUEST_RESOLVED.load(Ordering::SeqCst)
+}
+
+/// 读取聚焦请求文件，指向本进程的请求时聚焦窗口
+fn handle_focus_request(app: &AppHandle, path: &std::path::Path) {
+    let Some(own_id) = OWN_REQUEST_ID.get() else {
+        return;
+    };
+    let requested = std::fs::read_to_string(path).unwrap_or_default();
+    if requested.trim() != own_id || !has_own_pending_request() {
+        return;
+    }
+
+    if let Err(e) = crate::ui::notification::focus_main_window(app) {
+        log_important!(warn, "响应聚焦请求失败: {}", e);
+    }
+}
+
+/// 唤起一个待处理的弹窗
+///
+/// 本进程有待处理请求时直接聚焦，否则写入聚焦请求文件，由最早的待处理请求所在的等一下进程聚焦
+pub fn focus_pending_popup(app: &AppHandle) -> Result<(), String> {
+    if has_own_pending_request() {
+        return crate::ui::notification::focus_main_window(app);
+    }
+
+    let request_id = oldest_pending_request_id()
+        .map_err(|e| format!("查找待处理请求失败: {}", e))?
+        .ok_or_else(|| "没有待处理的请求".to_string())?;
+    let path = get_runtime_dir()
+        .map_err(|e| format!("获取运行时目录失败: {}", e))?
+        .join(app::FOCUS_REQUEST_FILE);
+    std::fs::write(&path, &request_id).map_err(|e| format!("写入聚焦请求失败: {}", e))
+}
+
+/// 监听运行时目录：标记文件创建或删除时重新统计待处理请求，聚焦请求文件写入时检查是否需要聚焦
+fn watch_pending_markers(app: &AppHandle) -> Result<(), String> {
+    let dir = get_runtime_dir().map_err(|e| format!("获取运行时目录失败: {}", e))?;
+
+    let app = app.clone();
+    let mut watcher = notify::recommended_watcher(move |event: notify::Result<notify::Event>| {
+        let Ok(event) = event else {
+            return;
+        };
+
+        if matches!(event.kind, EventKind::Create(_) | EventKind::Modify(_)) {
+            if let Some(path) = event
+                .paths
+                .iter()
+                .find(|path| path.file_name().is_some_and(|name| name == app::FOCUS_REQUEST_FILE))
+            {
+                handle_focus_request(&app, path);
+                return;
+            }
+        }
+
+        if !matches!(event.kind, EventKind::Create(_) | EventKind::Remove(_)) {
+            return;
+        }
+
+        let is_marker = event.paths.iter().any(|path| {
+            path.file_name()
+                .and_then(|name| name.to_str())
+                .is_some_and(|name| name.starts_with(app::PENDING_MARKER_PREFIX))
+        });
+        if is_marker {
+            if let Err(e) = refresh_pending_count(&app) {
+                log_debug!("{}", e);
+            }
+        }
+    })
+    .map_err(|e| format!("创建运行时目录监听失败: {}", e))?;
+
+    watcher
+        .watch(&dir, RecursiveMode::NonRecursive)
+        .map_err(|e| format!("监听运行时目录失败: {}", e))?;
+    let _ = PENDING_MARKER_WATCHER.set(Mutex::new(watcher));
+    Ok(())
+}
+
+/// 请求进入待处理状态（显示弹窗时调用）
+///
+/// 每个弹窗是独立的等一下进程，其他寸止进程的标记文件增减时重新统计
+pub fn mark_request_pending(app: &AppHandle, request_id: &str) {
+    let _ = OWN_REQUEST_ID.set(request_id.to_string());
+    if let Err(e) = refresh_pending_count(app) {
+        log_important!(warn, "{}", e);
+    }
+
+    if let Err(e) = watch_pending_markers(app) {
+        log_important!(warn, "{}", e);
+    }
+}
+
+/// 请求处理完成（响应、取消或超时后调用）
+pub fn mark_request_resolved(app: &AppHandle) {
+    REQUEST_RESOLVED.store(true, Ordering::SeqCst);
+    if let Err(e) = refresh_pending_count(app) {
+        log_important!(warn, "{}", e);
+    }
+}
+
+/// 获取所有寸止进程中待处理请求的数量
+#[tauri::command]
+pub async fn get_pending_count() -> Result<u32, String> {
+    current_pending_count()
+}
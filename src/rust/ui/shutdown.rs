@@ -0,0 +1,107 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+
+use tauri::{AppHandle, Manager};
+use tauri_plugin_global_shortcut::GlobalShortcutExt;
+
+use crate::config::{save_config, AppState};
+use crate::constants::app;
+use crate::log_important;
+
+/// 是否正在退出
+static SHUTTING_DOWN: AtomicBool = AtomicBool::new(false);
+
+/// 应用是否正在退出（退出过程中不再处理新的操作）
+pub fn is_shutting_down() -> bool {
+    SHUTTING_DOWN.load(Ordering::SeqCst)
+}
+
+/// 按顺序执行退出前的清理，返回进程退出码
+///
+/// 1. 标记正在退出
+/// 2. 取消未响应的 MCP 请求
+/// 3. 注销全局快捷键
+/// 4. 保存窗口位置和配置
+/// 5. 刷新日志
+///
+/// 清理步骤超过截止时间后直接放弃，保证应用一定能退出
+pub async fn graceful_shutdown(app: &AppHandle) -> i32 {
+    if SHUTTING_DOWN.swap(true, Ordering::SeqCst) {
+        log_important!(info, "应用已在退出中");
+    }
+
+    // 未响应的请求以取消结束，避免调用方一直等待
+    let exit_code = if crate::app::is_mcp_request_mode() {
+        if crate::app::cancel_pending_mcp_request("应用正在退出") {
+            crate::ui::pending::mark_request_resolved(app);
+        }
+        crate::app::finish_mcp_request()
+    } else {
+        0
+    };
+
+    let cleanup = async {
+        if let Err(e) = app.global_shortcut().unregister_all() {
+            log_important!(warn, "注销全局快捷键失败: {}", e);
+        }
+
+        if let Err(e) = crate::ui::window::save_window_position(app).await {
+            log_important!(warn, "退出前保存窗口位置失败: {}", e);
+        }
+
+        let state = app.state::<AppState>();
+        if let Err(e) = save_config(&state, app).await {
+            log_important!(warn, "退出前保存配置失败: {}", e);
+        }
+    };
+
+    if tokio::time::timeout(Duration::from_millis(app::SHUTDOWN_DEADLINE_MS), cleanup)
+        .await
+        .is_err()
+    {
+        log_important!(warn, "退出清理超时（{}ms），强制退出", app::SHUTDOWN_DEADLINE_MS);
+    }
+
+    log::logger().flush();
+    exit_code
+}
+
+/// 监听 SIGINT/SIGTERM，收到后执行正常退出流程
+pub fn setup_signal_handlers(app_handle: &AppHandle) {
+    let app_handle = app_handle.clone();
+
+    tauri::async_runtime::spawn(async move {
+        wait_for_termination_signal().await;
+        log_important!(info, "收到退出信号，开始退出");
+
+        if let Err(e) = crate::ui::exit::force_exit_app(app_handle).await {
+            log_important!(error, "退出失败: {}", e);
+        }
+    });
+}
+
+/// 等待终止信号
+async fn wait_for_termination_signal() {
+    #[cfg(unix)]
+    {
+        use tokio::signal::unix::{signal, SignalKind};
+
+        match signal(SignalKind::terminate()) {
+            Ok(mut terminate) => {
+                tokio::select! {
+                    _ = tokio::signal::ctrl_c() => {}
+                    _ = terminate.recv() => {}
+                }
+            }
+            Err(e) => {
+                log_important!(warn, "监听 SIGTERM 失败: {}", e);
+                let _ = tokio::signal::ctrl_c().await;
+            }
+        }
+    }
+
+    #[cfg(not(unix))]
+    {
+        let _ = tokio::signal::ctrl_c().await;
+    }
+}
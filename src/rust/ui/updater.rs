@@ -2,6 +2,8 @@ use tauri::{AppHandle, Emitter};
 use serde::{Deserialize, Serialize};
 use std::{fs, io::Write, path::PathBuf, process::Command};
 
+use crate::utils::get_runtime_dir;
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct UpdateInfo {
     pub available: bool,
@@ -264,7 +266,9 @@ async fn download_and_install_update_impl(app: &AppHandle, update_info: &UpdateI
     log::info!("📥 开始下载文件: {}", update_info.download_url);
 
     // 创建临时目录
-    let temp_dir = std::env::temp_dir().join("cunzhi_update");
+    let temp_dir = get_runtime_dir()
+        .map_err(|e| format!("获取运行时目录失败: {}", e))?
+        .join("update");
     fs::create_dir_all(&temp_dir)
         .map_err(|e| format!("创建临时目录失败: {}", e))?;
 
@@ -457,7 +461,9 @@ async fn install_from_archive(file_path: &PathBuf) -> Result<(), String> {
     log::info!("📍 当前可执行文件路径: {}", current_exe.display());
 
     // 创建临时解压目录
-    let temp_dir = std::env::temp_dir().join("cunzhi_extract");
+    let temp_dir = get_runtime_dir()
+        .map_err(|e| format!("获取运行时目录失败: {}", e))?
+        .join("extract");
     if temp_dir.exists() {
         fs::remove_dir_all(&temp_dir)
             .map_err(|e| format!("清理临时目录失败: {}", e))?;
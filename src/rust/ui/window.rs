@@ -1,4 +1,4 @@
-use tauri::{State, Manager};
+use tauri::{State, Manager, UserAttentionType};
 use crate::config::{AppState, save_config};
 use crate::constants::window;
 use serde::{Deserialize, Serialize};
@@ -135,3 +135,120 @@ pub async fn update_window_size(size_update: WindowSizeUpdate, state: State<'_,
 
     Ok(())
 }
+
+/// 保存主窗口当前位置到配置文件
+pub async fn save_window_position(app: &tauri::AppHandle) -> Result<(), String> {
+    let Some(main_window) = app.get_webview_window("main") else {
+        return Ok(());
+    };
+
+    // 最小化时的位置没有意义，跳过保存
+    if main_window.is_minimized().unwrap_or(false) {
+        return Ok(());
+    }
+
+    let position = main_window
+        .outer_position()
+        .map_err(|e| format!("获取窗口位置失败: {}", e))?;
+    let monitor_name = main_window
+        .current_monitor()
+        .ok()
+        .flatten()
+        .and_then(|monitor| monitor.name().cloned());
+
+    let state = app.state::<AppState>();
+    {
+        let mut config = state.config.lock().map_err(|e| format!("获取配置失败: {}", e))?;
+        config.ui_config.window_config.update_position(position.x, position.y, monitor_name);
+    }
+
+    save_config(&state, app).await.map_err(|e| format!("保存配置失败: {}", e))?;
+    log::debug!("窗口位置已保存: ({}, {})", position.x, position.y);
+    Ok(())
+}
+
+/// 重置窗口位置（清除保存的位置并居中窗口）
+#[tauri::command]
+pub async fn reset_window_position(state: State<'_, AppState>, app: tauri::AppHandle) -> Result<(), String> {
+    {
+        let mut config = state.config.lock().map_err(|e| format!("获取配置失败: {}", e))?;
+        config.ui_config.window_config.clear_position();
+    }
+
+    save_config(&state, &app).await.map_err(|e| format!("保存配置失败: {}", e))?;
+
+    if let Some(main_window) = app.get_webview_window("main") {
+        main_window.center().map_err(|e| format!("窗口居中失败: {}", e))?;
+    }
+
+    log::info!("窗口位置已重置");
+    Ok(())
+}
+
+/// 根据焦点策略判断是否应抢占窗口焦点
+pub fn should_take_focus(focus_policy: &str, urgent: bool) -> bool {
+    match focus_policy {
+        window::FOCUS_POLICY_ALWAYS => true,
+        window::FOCUS_POLICY_NEVER => false,
+        _ => urgent,
+    }
+}
+
+/// 收到请求时按焦点策略将窗口带到前台
+pub fn apply_focus_policy(app: &tauri::AppHandle, urgent: bool) -> Result<(), String> {
+    let focus_policy = {
+        let state = app.state::<AppState>();
+        let config = state.config.lock().map_err(|e| format!("获取配置失败: {}", e))?;
+        config.ui_config.focus_policy.clone()
+    };
+
+    let Some(main_window) = app.get_webview_window("main") else {
+        return Ok(());
+    };
+
+    if should_take_focus(&focus_policy, urgent) {
+        if let Err(e) = main_window.unminimize() {
+            log::warn!("取消窗口最小化失败: {}", e);
+        }
+        if let Err(e) = main_window.set_focus() {
+            log::warn!("设置窗口焦点失败: {}", e);
+        }
+        if let Err(e) = main_window.request_user_attention(Some(UserAttentionType::Critical)) {
+            log::warn!("请求用户注意失败: {}", e);
+        }
+        log::info!("已按焦点策略 {} 抢占窗口焦点 (urgent: {})", focus_policy, urgent);
+    } else {
+        // 不抢占焦点时，Windows 下闪烁任务栏提示用户
+        #[cfg(target_os = "windows")]
+        if let Err(e) = main_window.request_user_attention(Some(UserAttentionType::Informational)) {
+            log::warn!("闪烁任务栏失败: {}", e);
+        }
+        log::debug!("焦点策略 {} 未抢占窗口焦点 (urgent: {})", focus_policy, urgent);
+    }
+
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn get_focus_policy(state: State<'_, AppState>) -> Result<String, String> {
+    let config = state.config.lock().map_err(|e| format!("获取配置失败: {}", e))?;
+    Ok(config.ui_config.focus_policy.clone())
+}
+
+#[tauri::command]
+pub async fn set_focus_policy(focus_policy: String, state: State<'_, AppState>, app: tauri::AppHandle) -> Result<(), String> {
+    // 验证焦点策略值
+    if ![window::FOCUS_POLICY_ALWAYS, window::FOCUS_POLICY_NEVER, window::FOCUS_POLICY_ONLY_WHEN_URGENT]
+        .contains(&focus_policy.as_str())
+    {
+        return Err("无效的焦点策略，只支持 Always、Never、OnlyWhenUrgent".to_string());
+    }
+
+    {
+        let mut config = state.config.lock().map_err(|e| format!("获取配置失败: {}", e))?;
+        config.ui_config.focus_policy = focus_policy;
+    }
+
+    save_config(&state, &app).await.map_err(|e| format!("保存配置失败: {}", e))?;
+    Ok(())
+}
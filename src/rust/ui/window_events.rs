@@ -1,13 +1,38 @@
 use crate::config::AppState;
+use crate::constants::window::POSITION_SAVE_DEBOUNCE_MS;
 use crate::log_important;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
 use tauri::{AppHandle, Manager, WindowEvent};
 
 /// 设置窗口事件监听器
 pub fn setup_window_event_listeners(app_handle: &AppHandle) {
     if let Some(window) = app_handle.get_webview_window("main") {
         let app_handle_clone = app_handle.clone();
-        
+        // 窗口移动计数，用于位置保存防抖
+        let move_generation = Arc::new(AtomicU64::new(0));
+
         window.on_window_event(move |event| {
+            if let WindowEvent::Moved(_) = event {
+                let generation = move_generation.fetch_add(1, Ordering::SeqCst) + 1;
+                let move_generation = move_generation.clone();
+                let app_handle = app_handle_clone.clone();
+
+                tauri::async_runtime::spawn(async move {
+                    tokio::time::sleep(tokio::time::Duration::from_millis(POSITION_SAVE_DEBOUNCE_MS)).await;
+
+                    // 防抖期间又发生了移动，交给最后一次移动处理
+                    if move_generation.load(Ordering::SeqCst) != generation {
+                        return;
+                    }
+
+                    if let Err(e) = crate::ui::window::save_window_position(&app_handle).await {
+                        log_important!(warn, "保存窗口位置失败: {}", e);
+                    }
+                });
+                return;
+            }
+
             if let WindowEvent::CloseRequested { api, .. } = event {
                 // 阻止默认的关闭行为
                 api.prevent_close();
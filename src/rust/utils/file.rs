@@ -0,0 +1,34 @@
+use std::fs;
+use std::io;
+use std::path::Path;
+
+/// 原子写入文件：先写入同目录的临时文件再替换，避免写入中断导致文件损坏
+///
+/// 临时文件名包含进程ID和随机后缀，多个进程同时写入同一文件时不会互相覆盖临时文件
+pub fn write_file_atomic(path: &Path, contents: impl AsRef<[u8]>) -> io::Result<()> {
+    let mut temp_name = path.file_name().unwrap_or_default().to_os_string();
+    temp_name.push(format!(".{}.{}.tmp", std::process::id(), uuid::Uuid::new_v4().simple()));
+    let temp_path = path.with_file_name(temp_name);
+
+    let result = fs::write(&temp_path, contents).and_then(|()| fs::rename(&temp_path, path));
+    if result.is_err() {
+        let _ = fs::remove_file(&temp_path);
+    }
+    result
+}
+
+/// 持有文件的建议锁执行读取-修改-写入，避免多个进程同时更新时丢失修改
+///
+/// 文件本身会被原子替换，因此锁加在同目录的 `<文件名>.lock` 上，执行完成后释放
+pub fn with_file_lock<T>(path: &Path, update: impl FnOnce() -> anyhow::Result<T>) -> anyhow::Result<T> {
+    let mut lock_name = path.file_name().unwrap_or_default().to_os_string();
+    lock_name.push(".lock");
+
+    let lock_file = fs::OpenOptions::new()
+        .create(true)
+        .truncate(false)
+        .write(true)
+        .open(path.with_file_name(lock_name))?;
+    lock_file.lock()?;
+    update()
+}
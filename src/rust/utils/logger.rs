@@ -114,23 +114,22 @@ pub fn init_logger(config: LogConfig) -> Result<(), Box<dyn std::error::Error>>
 
 /// 自动检测模式并初始化日志系统
 pub fn auto_init_logger() -> Result<(), Box<dyn std::error::Error>> {
-    let args: Vec<String> = env::args().collect();
-    let is_mcp_mode = args.len() >= 3 && args[1] == "--mcp-request";
+    let is_mcp_mode = crate::app::is_mcp_request_mode();
     
     let config = if is_mcp_mode {
         // MCP 模式：输出到文件
-        let log_file_path = env::var("MCP_LOG_FILE")
-            .unwrap_or_else(|_| {
-                let temp_dir = env::temp_dir();
-                temp_dir.join("cunzhi-mcp.log").to_string_lossy().to_string()
-            });
-            
+        let log_file_path = env::var("MCP_LOG_FILE").ok().or_else(|| {
+            super::get_runtime_dir()
+                .ok()
+                .map(|dir| dir.join("cunzhi-mcp.log").to_string_lossy().to_string())
+        });
+
         LogConfig {
             level: env::var("RUST_LOG")
                 .unwrap_or_else(|_| "warn".to_string())
                 .parse::<LevelFilter>()
                 .unwrap_or(LevelFilter::Warn),
-            file_path: Some(log_file_path),
+            file_path: log_file_path,
             is_mcp_mode: true,
         }
     } else {
@@ -178,6 +177,14 @@ macro_rules! log_trace {
     };
 }
 
+/// 便利宏：带 trace_id 前缀的重要日志，便于跨进程追踪同一请求
+#[macro_export]
+macro_rules! log_with_trace {
+    ($level:ident, $trace_id:expr, $fmt:literal $(, $arg:expr)* $(,)?) => {
+        $crate::log_important!($level, concat!("[trace:{}] ", $fmt), $trace_id $(, $arg)*)
+    };
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1,3 +1,10 @@
+pub mod file;
 pub mod logger;
+pub mod runtime_dir;
 
+pub use file::{with_file_lock, write_file_atomic};
+pub use runtime_dir::{
+    cleanup_stale_runtime_files, count_pending_markers, create_pending_marker, get_runtime_dir, oldest_pending_request_id,
+    request_file_path, PendingMarker,
+};
 pub use logger::{LogConfig, init_logger, auto_init_logger};
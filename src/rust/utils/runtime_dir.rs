@@ -0,0 +1,255 @@
+use anyhow::Result;
+use std::fs::{self, File, TryLockError};
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+use std::time::{Duration, SystemTime};
+
+use crate::config::load_standalone_config;
+use crate::constants::app;
+use crate::log_debug;
+
+/// 解析后的运行时目录，进程内只解析一次
+static RUNTIME_DIR: OnceLock<PathBuf> = OnceLock::new();
+
+/// 获取运行时目录，所有临时文件（请求文件、日志、更新包等）都应写入此目录
+///
+/// 优先级：`CUNZHI_RUNTIME_DIR` 环境变量 > 配置中的 `runtime_dir` > 系统运行时/缓存目录下的 `cunzhi/`。
+/// 不使用全局可读的系统临时目录，新建目录时仅当前用户可访问
+pub fn get_runtime_dir() -> Result<PathBuf> {
+    let dir = match RUNTIME_DIR.get() {
+        Some(dir) => dir.clone(),
+        None => {
+            let configured = std::env::var(app::RUNTIME_DIR_ENV)
+                .ok()
+                .or_else(|| load_standalone_config().ok().and_then(|config| config.runtime_dir));
+            let dir = resolve_runtime_dir(configured.as_deref())?;
+            RUNTIME_DIR.get_or_init(|| dir).clone()
+        }
+    };
+
+    ensure_runtime_dir(&dir)?;
+    Ok(dir)
+}
+
+/// 根据配置的目录解析运行时目录，未配置时使用系统运行时/缓存目录
+fn resolve_runtime_dir(configured: Option<&str>) -> Result<PathBuf> {
+    let dir = match configured.map(str::trim).filter(|dir| !dir.is_empty()) {
+        Some(dir) => PathBuf::from(dir),
+        None => dirs::runtime_dir()
+            .or_else(dirs::cache_dir)
+            .ok_or_else(|| anyhow::anyhow!("无法获取运行时目录"))?
+            .join(app::RUNTIME_DIR_NAME),
+    };
+    Ok(dir)
+}
+
+/// 目录不存在时创建，并限制为仅当前用户可访问
+fn ensure_runtime_dir(dir: &Path) -> Result<()> {
+    if !dir.exists() {
+        fs::create_dir_all(dir)?;
+        restrict_permissions(dir)?;
+    }
+    Ok(())
+}
+
+#[cfg(unix)]
+fn restrict_permissions(dir: &std::path::Path) -> Result<()> {
+    use std::os::unix::fs::PermissionsExt;
+    fs::set_permissions(dir, fs::Permissions::from_mode(0o700))?;
+    Ok(())
+}
+
+#[cfg(not(unix))]
+fn restrict_permissions(_dir: &std::path::Path) -> Result<()> {
+    // Windows 上用户缓存目录默认仅当前用户可访问
+    Ok(())
+}
+
+/// 弹窗请求文件路径
+pub fn request_file_path(request_id: &str) -> Result<PathBuf> {
+    Ok(request_file_path_in(&get_runtime_dir()?, request_id))
+}
+
+fn request_file_path_in(dir: &Path, request_id: &str) -> PathBuf {
+    dir.join(format!("{}{}.json", app::REQUEST_FILE_PREFIX, request_id))
+}
+
+/// 待处理弹窗标记文件，持有期间锁定文件，释放时删除
+///
+/// 锁随进程退出自动释放，清理残留文件时据此判断创建标记的寸止进程是否仍然存活
+pub struct PendingMarker {
+    path: PathBuf,
+    _lock: File,
+}
+
+impl Drop for PendingMarker {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.path);
+    }
+}
+
+/// 创建待处理弹窗标记文件
+///
+/// 每个等待响应的弹窗对应一个标记文件，所有寸止进程共用同一目录，等一下据此统计总数
+pub fn create_pending_marker(request_id: &str) -> Result<PendingMarker> {
+    create_pending_marker_in(&get_runtime_dir()?, request_id)
+}
+
+fn create_pending_marker_in(dir: &Path, request_id: &str) -> Result<PendingMarker> {
+    let path = dir.join(format!(
+        "{}{}_{}",
+        app::PENDING_MARKER_PREFIX,
+        std::process::id(),
+        request_id
+    ));
+    let file = File::create(&path)?;
+    file.lock()?;
+    Ok(PendingMarker { path, _lock: file })
+}
+
+/// 标记文件是否仍被创建它的寸止进程持有
+fn is_marker_alive(path: &Path) -> bool {
+    let Ok(file) = File::open(path) else {
+        return false;
+    };
+    matches!(file.try_lock(), Err(TryLockError::WouldBlock))
+}
+
+/// 统计所有寸止进程中待处理弹窗的数量
+pub fn count_pending_markers() -> Result<usize> {
+    count_pending_markers_in(&get_runtime_dir()?)
+}
+
+fn count_pending_markers_in(dir: &Path) -> Result<usize> {
+    let count = fs::read_dir(dir)?
+        .flatten()
+        .filter(|entry| {
+            entry
+                .file_name()
+                .to_str()
+                .is_some_and(|name| name.starts_with(app::PENDING_MARKER_PREFIX))
+        })
+        .count();
+    Ok(count)
+}
+
+/// 获取最早进入待处理状态的请求ID
+///
+/// 标记文件名格式为 `pending_popup_<pid>_<request_id>`，按修改时间取最早的一个
+pub fn oldest_pending_request_id() -> Result<Option<String>> {
+    let dir = get_runtime_dir()?;
+    let oldest = fs::read_dir(&dir)?
+        .flatten()
+        .filter_map(|entry| {
+            let name = entry.file_name().to_str()?.to_string();
+            let (_pid, request_id) = name.strip_prefix(app::PENDING_MARKER_PREFIX)?.split_once('_')?;
+            let modified = entry.metadata().and_then(|metadata| metadata.modified()).ok()?;
+            Some((modified, request_id.to_string()))
+        })
+        .min_by_key(|(modified, _)| *modified)
+        .map(|(_, request_id)| request_id);
+    Ok(oldest)
+}
+
+/// 清理运行时目录中残留的请求文件和标记文件，返回清理的数量
+///
+/// 等一下或寸止异常退出时这些文件可能没有被删除，超过保留时间后清理。
+/// 仍被存活的寸止进程持有的标记文件不会被清理
+pub fn cleanup_stale_runtime_files() -> Result<usize> {
+    let dir = get_runtime_dir()?;
+    let removed = cleanup_stale_runtime_files_in(&dir, Duration::from_secs(app::STALE_RUNTIME_FILE_SECS))?;
+    if removed > 0 {
+        log_debug!("已清理残留运行时文件: {} 个, 目录: {}", removed, dir.display());
+    }
+    Ok(removed)
+}
+
+fn cleanup_stale_runtime_files_in(dir: &Path, max_age: Duration) -> Result<usize> {
+    let now = SystemTime::now();
+    let mut removed = 0;
+
+    for entry in fs::read_dir(dir)?.flatten() {
+        let file_name = entry.file_name();
+        let Some(name) = file_name.to_str() else {
+            continue;
+        };
+        let is_marker = name.starts_with(app::PENDING_MARKER_PREFIX);
+        if !is_marker && !name.starts_with(app::REQUEST_FILE_PREFIX) {
+            continue;
+        }
+
+        let expired = entry
+            .metadata()
+            .and_then(|metadata| metadata.modified())
+            .ok()
+            .and_then(|modified| now.duration_since(modified).ok())
+            .is_some_and(|age| age > max_age);
+        if !expired || (is_marker && is_marker_alive(&entry.path())) {
+            continue;
+        }
+
+        if fs::remove_file(entry.path()).is_ok() {
+            removed += 1;
+        }
+    }
+    Ok(removed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_runtime_dir(name: &str) -> PathBuf {
+        let configured = std::env::temp_dir().join(format!("cunzhi_test_{}_{}", name, std::process::id()));
+        let dir = resolve_runtime_dir(Some(&configured.to_string_lossy())).unwrap();
+        assert_eq!(dir, configured);
+
+        let _ = fs::remove_dir_all(&dir);
+        ensure_runtime_dir(&dir).unwrap();
+        dir
+    }
+
+    /// 将文件修改时间设置为一小时前
+    fn backdate(path: &Path) {
+        File::options()
+            .write(true)
+            .open(path)
+            .unwrap()
+            .set_modified(SystemTime::now() - Duration::from_secs(3600))
+            .unwrap();
+    }
+
+    /// 请求文件和标记文件都写入配置的运行时目录
+    #[test]
+    fn test_runtime_files_use_configured_dir() {
+        let dir = test_runtime_dir("paths");
+
+        assert_eq!(request_file_path_in(&dir, "req").parent(), Some(dir.as_path()));
+
+        let marker = create_pending_marker_in(&dir, "req").unwrap();
+        assert_eq!(marker.path.parent(), Some(dir.as_path()));
+        assert_eq!(count_pending_markers_in(&dir).unwrap(), 1);
+
+        drop(marker);
+        assert_eq!(count_pending_markers_in(&dir).unwrap(), 0);
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    /// 过期清理只删除没有进程持有的标记文件
+    #[test]
+    fn test_cleanup_keeps_live_markers() {
+        let dir = test_runtime_dir("cleanup");
+        let live = create_pending_marker_in(&dir, "live").unwrap();
+        let stale = dir.join(format!("{}0_stale", app::PENDING_MARKER_PREFIX));
+        fs::write(&stale, b"").unwrap();
+        backdate(&live.path);
+        backdate(&stale);
+
+        assert_eq!(cleanup_stale_runtime_files_in(&dir, Duration::from_secs(60)).unwrap(), 1);
+        assert!(live.path.exists());
+        assert!(!stale.exists());
+
+        drop(live);
+        let _ = fs::remove_dir_all(&dir);
+    }
+}